@@ -0,0 +1,441 @@
+use crate::piece::{Piece, PieceColor};
+use std::sync::OnceLock;
+
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)]; //(file, rank) steps
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+const KING_OFFSETS: [(i8, i8); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+static KNIGHT_ATTACKS: [u64; 64] = generate_leaper_attacks(&KNIGHT_OFFSETS);
+static KING_ATTACKS: [u64; 64] = generate_leaper_attacks(&KING_OFFSETS);
+static PAWN_ATTACKS: [[u64; 64]; 2] = [
+    generate_leaper_attacks(&[(-1, 1), (1, 1)]),
+    generate_leaper_attacks(&[(-1, -1), (1, -1)]),
+];
+static SLIDING_ATTACKS: OnceLock<SlidingAttacks> = OnceLock::new();
+
+//multipliers that hash every blocker subset of a square's mask to a slot holding its attacks without harmful collisions, found by a seeded search over sparse random numbers
+const ROOK_MAGICS: [u64; 64] = [
+    0x2080_0020_8040_0010,
+    0x00C0_0020_0140_1000,
+    0x2100_1100_0840_2002,
+    0x0880_0800_8104_1000,
+    0x0200_0200_2004_1008,
+    0x2300_0400_0801_0012,
+    0x0C00_2830_0400_8201,
+    0x0180_0100_0040_7A80,
+    0x0168_8000_8040_0020,
+    0x0010_4000_4020_1000,
+    0x1001_0020_0100_1048,
+    0x1001_0024_0810_0100,
+    0x0801_0004_0801_0012,
+    0x4001_0002_0900_0400,
+    0x08A2_0004_C802_0001,
+    0x2002_8011_4500_2280,
+    0x0080_8600_2100_4200,
+    0x0010_00C0_0940_2002,
+    0x00B0_0020_0400_2800,
+    0x100A_8080_1002_0800,
+    0x8101_0100_0800_0410,
+    0x0244_0080_0200_0480,
+    0x0000_0400_1081_0208,
+    0x2000_0200_0044_8534,
+    0x4104_4004_8000_8033,
+    0x0000_8101_0020_4000,
+    0x0440_4309_0020_0010,
+    0x4600_2409_0010_0100,
+    0x0060_0800_8004_0080,
+    0x0001_0003_0008_0400,
+    0x0004_0844_0001_1002,
+    0x0023_0402_0000_8041,
+    0x0580_0500_4300_2080,
+    0x0400_8040_0280_2008,
+    0x0001_0020_0100_4010,
+    0x1000_2009_0100_1000,
+    0x4410_8008_0180_0C00,
+    0xA012_0038_0600_1004,
+    0x0020_1001_0400_8802,
+    0x0004_8084_0200_0041,
+    0x0010_4001_7089_8000,
+    0x0080_5000_2000_4004,
+    0x1040_4080_1202_0020,
+    0x8010_0400_0800_4040,
+    0x2001_0801_0011_0004,
+    0x0000_0200_0400_8080,
+    0x0021_0108_1004_0002,
+    0x0800_008C_4302_0024,
+    0x0000_8000_2100_5100,
+    0x0070_2010_4000_8080,
+    0x0000_D042_8200_6A00,
+    0x0010_0144_0008_0240,
+    0x0001_0801_1005_0100,
+    0x0012_0008_1024_0600,
+    0x0402_0008_0104_0200,
+    0x0281_0010_8A00_4100,
+    0x0050_8003_0010_2045,
+    0x8208_2100_4012_0882,
+    0x8010_6001_0118_3441,
+    0x020B_0009_1000_6045,
+    0x0241_0010_0248_0005,
+    0x0081_0004_0088_0241,
+    0x0000_0090_0802_4124,
+    0x0048_1229_8041_0402,
+];
+const BISHOP_MAGICS: [u64; 64] = [
+    0x0848_0208_2204_0013,
+    0x8010_A400_8582_1200,
+    0x0008_0084_3084_0822,
+    0x0808_0481_0804_0000,
+    0x1304_0421_0000_8104,
+    0x5001_0120_1020_4023,
+    0x8104_8801_B820_0420,
+    0x200A_0080_8401_2000,
+    0x0040_1020_0104_2084,
+    0x840A_5050_4242_8020,
+    0x0000_7001_0220_2920,
+    0x4410_1C0C_1080_0002,
+    0x0040_0404_2200_0000,
+    0x0180_0208_0209_0202,
+    0x4020_0208_1104_1202,
+    0x0001_0430_8C04_2000,
+    0x4140_6610_0242_4400,
+    0x0028_0120_0801_0460,
+    0x0188_0621_0200_2A00,
+    0x0014_0048_4010_2008,
+    0x0105_0002_9040_0002,
+    0x8001_0222_0041_0400,
+    0x104A_0419_1801_3446,
+    0x008A_0000_8200_8238,
+    0x04A0_0600_0810_0430,
+    0x0008_2200_0882_0801,
+    0x2508_0412_0800_5010,
+    0x4008_0802_0020_2020,
+    0x2441_0010_1300_4000,
+    0x0030_0080_6040_7000,
+    0x4008_1080_0042_0800,
+    0x0012_0210_5029_0100,
+    0x0210_0804_8220_0500,
+    0xCC01_1120_4810_0480,
+    0x0020_4028_0650_0440,
+    0x0004_8E00_8058_0080,
+    0x0040_1020_2002_0080,
+    0x0028_0104_4008_0807,
+    0x4601_0411_0800_8800,
+    0x8040_810E_0410_4200,
+    0x9012_1011_0400_088A,
+    0xA003_0802_1208_1050,
+    0x00C1_0040_4840_1004,
+    0x9000_00A0_1440_0800,
+    0x0008_0210_4040_5401,
+    0x4020_0082_0600_2090,
+    0x0004_1904_2403_0100,
+    0x0424_008A_0202_6250,
+    0x8004_0882_5090_0040,
+    0x1C00_4300_88A0_4200,
+    0x0001_0200_9404_0001,
+    0x8040_2100_2088_0061,
+    0x2010_0404_5044_2032,
+    0x0800_8408_5004_4001,
+    0x0004_0408_0214_0004,
+    0x0004_080A_0422_2020,
+    0x8088_8021_1002_2000,
+    0x1081_A104_1611_4400,
+    0x0205_010A_2406_0820,
+    0x0000_0007_2041_1080,
+    0x1008_0002_0843_0400,
+    0x580C_0260_2881_0840,
+    0x8020_2044_1020_A110,
+    0x12C0_0224_0102_0018,
+];
+
+///Where a square's attacks live in the shared table and how to hash blockers to them.
+struct Magic {
+    mask: u64, //squares whose occupancy matters, board edges excluded
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    fn get_index(&self, occupancy: u64) -> usize {
+        self.offset + ((occupancy & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+struct SlidingAttacks {
+    rook_magics: Vec<Magic>,
+    bishop_magics: Vec<Magic>,
+    attacks: Vec<u64>,
+}
+
+impl SlidingAttacks {
+    fn get() -> &'static SlidingAttacks {
+        SLIDING_ATTACKS.get_or_init(SlidingAttacks::generate)
+    }
+
+    ///Fills the attack table by walking the rays for every blocker subset of every square's mask.
+    fn generate() -> SlidingAttacks {
+        let mut attacks = vec![];
+        let mut rook_magics = vec![];
+        let mut bishop_magics = vec![];
+
+        for (directions, magics, sliding_magics) in [
+            (&ROOK_DIRECTIONS, &ROOK_MAGICS, &mut rook_magics),
+            (&BISHOP_DIRECTIONS, &BISHOP_MAGICS, &mut bishop_magics),
+        ] {
+            for square in 0..64u8 {
+                let mask = get_blocker_mask(square, directions);
+                let magic = Magic {
+                    mask,
+                    magic: magics[square as usize],
+                    shift: 64 - mask.count_ones(),
+                    offset: attacks.len(),
+                };
+                attacks.resize(attacks.len() + (1 << mask.count_ones()), 0u64);
+
+                //walk every subset of the mask with the carry-rippler trick
+                let mut occupancy = 0u64;
+                loop {
+                    attacks[magic.get_index(occupancy)] =
+                        get_ray_attacks(square, occupancy, directions);
+                    occupancy = occupancy.wrapping_sub(mask) & mask;
+                    if occupancy == 0 {
+                        break;
+                    }
+                }
+                sliding_magics.push(magic);
+            }
+        }
+
+        SlidingAttacks {
+            rook_magics,
+            bishop_magics,
+            attacks,
+        }
+    }
+}
+
+pub fn get_rook_attacks(square: u8, occupancy: u64) -> u64 {
+    let sliding_attacks = SlidingAttacks::get();
+    sliding_attacks.attacks[sliding_attacks.rook_magics[square as usize].get_index(occupancy)]
+}
+
+pub fn get_bishop_attacks(square: u8, occupancy: u64) -> u64 {
+    let sliding_attacks = SlidingAttacks::get();
+    sliding_attacks.attacks[sliding_attacks.bishop_magics[square as usize].get_index(occupancy)]
+}
+
+pub fn get_queen_attacks(square: u8, occupancy: u64) -> u64 {
+    get_rook_attacks(square, occupancy) | get_bishop_attacks(square, occupancy)
+}
+
+pub fn get_knight_attacks(square: u8) -> u64 {
+    KNIGHT_ATTACKS[square as usize]
+}
+
+pub fn get_king_attacks(square: u8) -> u64 {
+    KING_ATTACKS[square as usize]
+}
+
+///Gets the squares a pawn of the given color attacks diagonally from square.
+pub fn get_pawn_attacks(color: PieceColor, square: u8) -> u64 {
+    PAWN_ATTACKS[Piece::color_to_index(color)][square as usize]
+}
+
+const fn generate_leaper_attacks(offsets: &[(i8, i8)]) -> [u64; 64] {
+    let mut attacks = [0u64; 64];
+    let mut square = 0;
+    while square < 64 {
+        let mut offset = 0;
+        while offset < offsets.len() {
+            let file = (square % 8) as i8 + offsets[offset].0;
+            let rank = (square / 8) as i8 + offsets[offset].1;
+            if file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+                attacks[square] |= 1u64 << (rank * 8 + file);
+            }
+            offset += 1;
+        }
+        square += 1;
+    }
+    attacks
+}
+
+///Walks each ray from square until it leaves the board or hits a blocker, the blocker's square included.
+fn get_ray_attacks(square: u8, occupancy: u64, directions: &[(i8, i8)]) -> u64 {
+    let mut attacks = 0u64;
+
+    for (file_step, rank_step) in directions {
+        let mut file = (square % 8) as i8 + file_step;
+        let mut rank = (square / 8) as i8 + rank_step;
+        while (0..8).contains(&file) && (0..8).contains(&rank) {
+            let bit = 1u64 << (rank * 8 + file);
+            attacks |= bit;
+            if occupancy & bit != 0 {
+                break;
+            }
+            file += file_step;
+            rank += rank_step;
+        }
+    }
+
+    attacks
+}
+
+///Gets the squares whose occupancy can block a slider on square, the last square of each ray never blocks anything.
+fn get_blocker_mask(square: u8, directions: &[(i8, i8)]) -> u64 {
+    let mut mask = 0u64;
+
+    for (file_step, rank_step) in directions {
+        let mut file = (square % 8) as i8 + file_step;
+        let mut rank = (square / 8) as i8 + rank_step;
+        while (0..8).contains(&(file + file_step)) && (0..8).contains(&(rank + rank_step)) {
+            mask |= 1u64 << (rank * 8 + file);
+            file += file_step;
+            rank += rank_step;
+        }
+    }
+
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{hint::black_box, time::Instant};
+
+    ///Sparse pseudo-random occupancies, roughly what a middlegame board looks like.
+    fn random_occupancies(count: usize) -> Vec<u64> {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        (0..count).map(|_| next() & next() & next()).collect()
+    }
+
+    #[test]
+    fn magic_lookups_match_ray_walks() {
+        for occupancy in random_occupancies(2_000) {
+            for square in 0..64 {
+                assert_eq!(
+                    get_rook_attacks(square, occupancy),
+                    get_ray_attacks(square, occupancy, &ROOK_DIRECTIONS)
+                );
+                assert_eq!(
+                    get_bishop_attacks(square, occupancy),
+                    get_ray_attacks(square, occupancy, &BISHOP_DIRECTIONS)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn leaper_tables_stay_on_the_board() {
+        //corner squares must not wrap to the other side of the board
+        assert_eq!(get_knight_attacks(0).count_ones(), 2);
+        assert_eq!(get_knight_attacks(63).count_ones(), 2);
+        assert_eq!(get_king_attacks(7).count_ones(), 3);
+        assert_eq!(get_pawn_attacks(PieceColor::White, 15), 1 << 22);
+        assert_eq!(get_pawn_attacks(PieceColor::Black, 48), 1 << 41);
+    }
+
+    ///The slider walk the magic lookups replaced, minus its checker bookkeeping: offsets stepped with bounds checks and collected into a Vec.
+    fn walk_offsets(square: u8, occupancy: u64, directions: &[i8]) -> u64 {
+        let mut possible_attacks = vec![];
+        for direction in directions {
+            let mut destination = square as i8;
+
+            for _ in 0..7 {
+                destination += direction;
+                if !(0..=63).contains(&destination) {
+                    break; //out of vertical bounds
+                }
+
+                let from_file = square as i8 % 8;
+                let to_file = destination % 8;
+                let file_diff = (from_file - to_file).abs();
+                let rank_diff = (square as i8 / 8 - destination / 8).abs();
+                match direction {
+                    1 if to_file <= from_file => break,
+                    -1 if to_file >= from_file => break,
+                    7 | -7 | 9 | -9 if file_diff != rank_diff => break,
+                    _ => {}
+                }
+
+                possible_attacks.push(destination as u8);
+                if occupancy & (1u64 << destination) != 0 {
+                    break;
+                }
+            }
+        }
+
+        possible_attacks
+            .into_iter()
+            .fold(0u64, |attacks, destination| attacks | (1u64 << destination))
+    }
+
+    ///Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn benchmark_sliding_attacks() {
+        const ROOK_OFFSETS: [i8; 4] = [1, -1, 8, -8];
+        const BISHOP_OFFSETS: [i8; 4] = [7, -7, 9, -9];
+        let occupancies = random_occupancies(20_000);
+        get_rook_attacks(0, 0); //build the tables outside the timed loop
+        for square in 0..64 {
+            assert_eq!(
+                walk_offsets(square, occupancies[0], &ROOK_OFFSETS),
+                get_rook_attacks(square, occupancies[0])
+            );
+            assert_eq!(
+                walk_offsets(square, occupancies[0], &BISHOP_OFFSETS),
+                get_bishop_attacks(square, occupancies[0])
+            );
+        }
+
+        let start = Instant::now();
+        for occupancy in &occupancies {
+            for square in 0..64 {
+                black_box(walk_offsets(square, *occupancy, &ROOK_OFFSETS));
+                black_box(walk_offsets(square, *occupancy, &BISHOP_OFFSETS));
+            }
+        }
+        let offset_walk = start.elapsed();
+
+        let start = Instant::now();
+        for occupancy in &occupancies {
+            for square in 0..64 {
+                black_box(get_rook_attacks(square, *occupancy));
+                black_box(get_bishop_attacks(square, *occupancy));
+            }
+        }
+        let magic = start.elapsed();
+
+        println!(
+            "offset walk: {:?}, magic lookup: {:?}, {:.1}x faster",
+            offset_walk,
+            magic,
+            offset_walk.as_secs_f64() / magic.as_secs_f64()
+        );
+    }
+}
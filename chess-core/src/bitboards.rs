@@ -0,0 +1,1666 @@
+use crate::{
+    attacks,
+    board::{Board, POCKET_GROUPS},
+    move_error::MoveError,
+    move_list::{GenerationMode, Move, MoveFlag, MoveList},
+    move_record::MoveRecord,
+    piece::{Piece, PieceColor, PieceGroup},
+    position::Positions,
+    status::{DrawReason, GameStatus},
+    variant::Variant,
+    zobrist::ZOBRIST_KEYS,
+};
+
+pub type PieceBitboards = [u64; 12];
+pub type ColorBitboards = [u64; 2];
+
+const BACK_RANKS: u64 = 0xFF00_0000_0000_00FF; //pawns are never dropped on the first or last rank
+
+#[derive(Clone)]
+pub struct Bitboards {
+    pub all_pieces: PieceBitboards, //idx order: wp, wr, wn, wb, wq, wk, bp, br, bn, bb, bq, bk
+    attacks: ColorBitboards,
+    checking_pieces: ColorBitboards,
+    en_passant: u64,
+}
+
+impl Bitboards {
+    ///Creates and returns new Bitboards instance with all_pieces set to initial piece bitboards.
+    pub fn new() -> Self {
+        Self {
+            all_pieces: Bitboards::create_piece_bitboards(),
+            attacks: [
+                Bitboards::create_empty_bitboard(),
+                Bitboards::create_empty_bitboard(),
+            ],
+            checking_pieces: [
+                Bitboards::create_empty_bitboard(),
+                Bitboards::create_empty_bitboard(),
+            ],
+            en_passant: Bitboards::create_empty_bitboard(),
+        }
+    }
+
+    ///Creates and returns new Bitboards instance with all_pieces set from the given squares.
+    pub fn from_squares(squares: &[Option<Piece>], en_passant: Option<u8>) -> Self {
+        let mut all_pieces: PieceBitboards = [Bitboards::create_empty_bitboard(); 12];
+        for (square, occupant) in squares.iter().enumerate() {
+            if let Some(piece) = occupant {
+                all_pieces[piece.to_index()] |= Bitboards::convert_to_bit(square as u8);
+            }
+        }
+
+        Self {
+            all_pieces,
+            attacks: [
+                Bitboards::create_empty_bitboard(),
+                Bitboards::create_empty_bitboard(),
+            ],
+            checking_pieces: [
+                Bitboards::create_empty_bitboard(),
+                Bitboards::create_empty_bitboard(),
+            ],
+            en_passant: en_passant.map_or(Bitboards::create_empty_bitboard(), |square| {
+                Bitboards::convert_to_bit(square)
+            }),
+        }
+    }
+
+    ///Gets the square a pawn would move to when capturing en passant, if any.
+    pub fn get_en_passant_square(&self) -> Option<u8> {
+        match self.en_passant {
+            0 => None,
+            en_passant => Some(en_passant.trailing_zeros() as u8),
+        }
+    }
+
+    ///Gets all legal moves and returns a vector containing bitboards for each position, drops are left to get_all_legal_drops.
+    pub fn get_all_legal_moves(&mut self, board: &mut Board) -> Vec<u64> {
+        let mut all_legal_moves = vec![Bitboards::create_empty_bitboard(); 64];
+        for legal_move in &self.generate_legal_moves(board, GenerationMode::All) {
+            if legal_move.drop().is_none() {
+                all_legal_moves[legal_move.origin() as usize] |=
+                    Bitboards::convert_to_bit(legal_move.destination());
+            }
+        }
+        all_legal_moves
+    }
+
+    ///Gets the squares each pocket piece can legally be dropped on, in POCKET_GROUPS order.
+    pub fn get_all_legal_drops(&mut self, board: &Board) -> [u64; 5] {
+        let mut all_legal_drops = [Bitboards::create_empty_bitboard(); 5];
+        for legal_move in &self.generate_legal_moves(board, GenerationMode::Quiets) {
+            if let Some(pocket_index) = legal_move
+                .drop()
+                .and_then(|group| POCKET_GROUPS.iter().position(|pocket| *pocket == group))
+            {
+                all_legal_drops[pocket_index] |=
+                    Bitboards::convert_to_bit(legal_move.destination());
+            }
+        }
+        all_legal_drops
+    }
+
+    ///Generates the legal moves for the side to move as a MoveList, limited to the given mode.
+    pub fn generate_legal_moves(&mut self, board: &Board, mode: GenerationMode) -> MoveList {
+        //a variant win ends the game before the losing side gets to move
+        if board.variant.get_position_win(board, self).is_some() {
+            self.attacks = self.get_all_attacks();
+            return MoveList::new();
+        }
+        if !board.variant.is_capture_forced() {
+            return self.generate_moves(board, mode, true);
+        }
+
+        //quiet moves are only legal when there is nothing to capture
+        let captures = self.generate_moves(board, GenerationMode::Captures, true);
+        match (captures.is_empty(), mode) {
+            (false, GenerationMode::Quiets) => MoveList::new(),
+            (false, _) | (true, GenerationMode::Captures) => captures,
+            (true, _) => self.generate_moves(board, mode, true),
+        }
+    }
+
+    ///Generates moves by piece movement rules alone, some may leave the own king in check.
+    pub fn generate_pseudo_legal_moves(&mut self, board: &Board, mode: GenerationMode) -> MoveList {
+        self.generate_moves(board, mode, false)
+    }
+
+    fn generate_moves(&mut self, board: &Board, mode: GenerationMode, is_legal: bool) -> MoveList {
+        self.attacks = self.get_all_attacks();
+        let enemy_pieces = self.get_color_occupancy(Piece::get_opposite_color(board.turn_color));
+        let mut move_list = MoveList::new();
+
+        for (piece_index, mut origins) in self.all_pieces.into_iter().enumerate() {
+            let Some(piece) = Piece::from_index(piece_index) else {
+                continue;
+            };
+            if piece.color != board.turn_color {
+                continue;
+            }
+            let capture_targets = match piece.group {
+                PieceGroup::Pawn => enemy_pieces | self.en_passant,
+                _ => enemy_pieces,
+            };
+
+            while origins != 0 {
+                let origin = origins.trailing_zeros() as u8;
+                origins &= origins - 1;
+
+                let mut destinations = self.get_pseudo_legal_moves(board, origin, piece);
+                destinations &= match mode {
+                    GenerationMode::All => u64::MAX,
+                    GenerationMode::Captures => capture_targets,
+                    GenerationMode::Quiets => !capture_targets,
+                };
+                //without a royal king every move the pieces can make is legal
+                if is_legal && board.variant.has_royal_king() {
+                    destinations =
+                        self.create_legal_moves_bitboard(board, piece, destinations, origin);
+                }
+
+                while destinations != 0 {
+                    let destination = destinations.trailing_zeros() as u8;
+                    destinations &= destinations - 1;
+                    self.push_moves(
+                        &mut move_list,
+                        board,
+                        piece,
+                        origin,
+                        destination,
+                        enemy_pieces,
+                    );
+                }
+            }
+        }
+
+        if board.variant.has_drops() && mode != GenerationMode::Captures {
+            for group in POCKET_GROUPS {
+                let mut destinations = self.get_drop_destinations(board, group, is_legal);
+                while destinations != 0 {
+                    let destination = destinations.trailing_zeros() as u8;
+                    destinations &= destinations - 1;
+                    move_list.push(Move::new_drop(group, destination));
+                }
+            }
+        }
+
+        move_list
+    }
+
+    ///Gets the squares the side to move can drop a pocket piece of the group on, legal drops must also block any check.
+    fn get_drop_destinations(&self, board: &Board, group: PieceGroup, is_legal: bool) -> u64 {
+        if board.get_pocket_count(board.turn_color, group) == 0 {
+            return Bitboards::create_empty_bitboard();
+        }
+        let mut destinations = !self.get_all_pieces_on_one_bitboard();
+        if group == PieceGroup::Pawn {
+            destinations &= !BACK_RANKS;
+        }
+
+        if is_legal && self.is_checked(board.turn_color) {
+            //a dropped piece can only interpose, and never against a double or contact check
+            let king_bitboard =
+                self.all_pieces[Piece::to_piece_index(board.turn_color, PieceGroup::King)];
+            let checkers_bitboard = self.checking_pieces
+                [Piece::color_to_index(Piece::get_opposite_color(board.turn_color))];
+            destinations &= match checkers_bitboard.count_ones() {
+                1 => Bitboards::get_rays_from_bitboards(checkers_bitboard, king_bitboard),
+                _ => Bitboards::create_empty_bitboard(),
+            };
+        }
+        destinations
+    }
+
+    ///Flags a generated move and adds it, once per piece choice for promotions.
+    fn push_moves(
+        &self,
+        move_list: &mut MoveList,
+        board: &Board,
+        piece: Piece,
+        origin: u8,
+        destination: u8,
+        enemy_pieces: u64,
+    ) {
+        let destination_bit = Bitboards::convert_to_bit(destination);
+        let is_capture = enemy_pieces & destination_bit != 0;
+
+        if Bitboards::is_promotion_move(piece, destination) {
+            for group in board.variant.get_promotion_groups().iter().copied() {
+                let flag = match is_capture {
+                    true => MoveFlag::PromotionCapture(group),
+                    false => MoveFlag::Promotion(group),
+                };
+                move_list.push(Move::new(origin, destination, flag));
+            }
+            return;
+        }
+
+        let flag = match piece.group {
+            _ if is_capture => MoveFlag::Capture,
+            PieceGroup::Pawn if destination_bit == self.en_passant => MoveFlag::EnPassant,
+            PieceGroup::Pawn if origin.abs_diff(destination) == 16 => MoveFlag::DoublePawnPush,
+            PieceGroup::King => match self.get_castling_side(board, origin, destination) {
+                Some(PieceGroup::King) => MoveFlag::KingsideCastle,
+                Some(_) => MoveFlag::QueensideCastle,
+                None => MoveFlag::Quiet,
+            },
+            _ => MoveFlag::Quiet,
+        };
+        move_list.push(Move::new(origin, destination, flag));
+    }
+
+    ///Plays a generated move, the turn color is left for the caller to toggle.
+    pub fn make_move(
+        &mut self,
+        board: &mut Board,
+        chess_move: Move,
+    ) -> Result<MoveRecord, MoveError> {
+        let move_record = match (chess_move.drop(), chess_move.promotion()) {
+            (Some(group), _) => self.drop_piece(board, group, chess_move.destination()),
+            (None, Some(promotion)) => self.promote_pawn(
+                board,
+                chess_move.origin(),
+                chess_move.destination(),
+                promotion,
+            ),
+            (None, None) => self.move_piece(board, chess_move.origin(), chess_move.destination()),
+        }?;
+
+        if board.variant.counts_checks() {
+            self.attacks = self.get_all_attacks();
+            let mover = move_record.piece.color;
+            if self.is_checked(Piece::get_opposite_color(mover)) {
+                board.update_checks_given(
+                    mover,
+                    board.checks_given[Piece::color_to_index(mover)] + 1,
+                );
+            }
+        }
+        Ok(move_record)
+    }
+
+    ///Gets the game status for the side to move, legal_moves must come from get_all_legal_moves on the current position.
+    pub fn get_game_status(&self, board: &Board, legal_moves: &[u64]) -> GameStatus {
+        //drops aren't in legal_moves, in crazyhouse they may be the only way out of check
+        let has_legal_moves = legal_moves.iter().any(|moves| *moves != 0)
+            || (board.variant.has_drops()
+                && POCKET_GROUPS
+                    .iter()
+                    .any(|group| self.get_drop_destinations(board, *group, true) != 0));
+        let is_checked = board.variant.has_royal_king() && self.is_checked(board.turn_color);
+
+        if let Some(variant_win) = board.variant.get_position_win(board, self) {
+            return GameStatus::VariantWin(variant_win);
+        }
+        if !has_legal_moves {
+            //mate and stalemate take precedence over any draw rule
+            return board.variant.get_no_moves_status(board, self, is_checked);
+        }
+        if let Some(draw_reason) = self.get_automatic_draw(board) {
+            return GameStatus::Draw(draw_reason);
+        }
+        if let Some(draw_reason) = board.claimed_draw {
+            return GameStatus::Draw(draw_reason);
+        }
+
+        match is_checked {
+            true => GameStatus::Check,
+            false => GameStatus::Ongoing,
+        }
+    }
+
+    ///Gets the draw the side to move may claim, if any.
+    pub fn get_claimable_draw(&self, board: &Board) -> Option<DrawReason> {
+        if board.halfmove_clock >= 100 {
+            return Some(DrawReason::FiftyMoveRule);
+        }
+        if board.count_repetitions() >= 3 {
+            return Some(DrawReason::ThreefoldRepetition);
+        }
+        None
+    }
+
+    ///Gets the draw that ends the game without a claim, if any.
+    fn get_automatic_draw(&self, board: &Board) -> Option<DrawReason> {
+        if board.variant.is_insufficient_material(board, self) {
+            return Some(DrawReason::InsufficientMaterial);
+        }
+        if board.count_repetitions() >= 5 {
+            return Some(DrawReason::FivefoldRepetition);
+        }
+        if board.halfmove_clock >= 150 {
+            return Some(DrawReason::SeventyFiveMoveRule);
+        }
+        None
+    }
+
+    ///Checks if neither side has the material to deliver mate: K vs K, K+minor vs K, or bishops all on one square color.
+    pub(crate) fn is_insufficient_material(&self) -> bool {
+        let light_squares = 0x55AA_55AA_55AA_55AAu64;
+        let mut minor_pieces = 0u64;
+        let mut bishops = 0u64;
+
+        for color in [PieceColor::White, PieceColor::Black] {
+            for group in [PieceGroup::Pawn, PieceGroup::Rook, PieceGroup::Queen] {
+                if self.all_pieces[Piece::to_piece_index(color, group)] != 0 {
+                    return false;
+                }
+            }
+            let color_bishops = self.all_pieces[Piece::to_piece_index(color, PieceGroup::Bishop)];
+            bishops |= color_bishops;
+            minor_pieces |=
+                color_bishops | self.all_pieces[Piece::to_piece_index(color, PieceGroup::Knight)];
+        }
+
+        minor_pieces.count_ones() <= 1
+            || (minor_pieces == bishops
+                && (bishops & light_squares == 0 || bishops & !light_squares == 0))
+    }
+
+    ///Recomputes both colors' attacks, e.g. so is_checked can be asked about a freshly parsed position.
+    pub fn update_attacks(&mut self) {
+        self.attacks = self.get_all_attacks();
+    }
+
+    ///Gets all attacks and returns a bitboard of attacked squares for each color.
+    fn get_all_attacks(&mut self) -> ColorBitboards {
+        self.attacks = [0u64, 0u64];
+        self.checking_pieces = [0u64, 0u64];
+        let mut all_attacks: ColorBitboards = [0u64, 0u64];
+
+        for (piece_index, mut origins) in self.all_pieces.into_iter().enumerate() {
+            let Some(piece) = Piece::from_index(piece_index) else {
+                continue;
+            };
+            while origins != 0 {
+                let origin = origins.trailing_zeros() as u8;
+                origins &= origins - 1;
+                all_attacks[Piece::color_to_index(piece.color)] |= self.get_attacks(origin, piece);
+            }
+        }
+
+        all_attacks
+    }
+
+    ///Moves piece by updating moved piece's bitboard and any captured piece's bitboard, pawns reaching the last rank must use promote_pawn.
+    ///Piece movement rules are not checked, untrusted moves go through Game::make_move which matches them against the legal set.
+    pub fn move_piece(
+        &mut self,
+        board: &mut Board,
+        origin: u8,
+        destination: u8,
+    ) -> Result<MoveRecord, MoveError> {
+        self.validate_promotion(board, origin, destination, None)?;
+        self.relocate_piece(board, origin, destination)
+    }
+
+    fn relocate_piece(
+        &mut self,
+        board: &mut Board,
+        origin: u8,
+        destination: u8,
+    ) -> Result<MoveRecord, MoveError> {
+        //get piece to move
+        if let Some(piece) = self.get_occupant(origin) {
+            //castles are checked in full when generated, in Chess960 the destination holds the king's own rook
+            let castling_side = self.get_castling_side(board, origin, destination);
+            let origin_bitboard = &self.all_pieces[piece.to_index()];
+            match (castling_side, board.variant.has_royal_king()) {
+                (Some(_), _) => {}
+                //check move validity
+                (None, true) => {
+                    self.is_valid_move(board.variant, origin, destination, origin_bitboard, piece)?
+                }
+                //pins and checks don't matter when the king can be captured
+                (None, false) => {
+                    self.validate_destination(origin, destination, origin_bitboard, piece)?
+                }
+            }
+            let mut move_record = MoveRecord {
+                piece,
+                origin,
+                destination,
+                captured_piece: None,
+                captured_square: destination,
+                promotion: None,
+                drop: None,
+                castling_rook: None,
+                exploded_pieces: [None; 8],
+                prior_can_kingside_castle: board.can_kingside_castle,
+                prior_can_queenside_castle: board.can_queenside_castle,
+                prior_en_passant: self.get_en_passant_square(),
+                prior_halfmove_clock: board.halfmove_clock,
+                prior_fullmove_number: board.fullmove_number,
+                prior_checks_given: board.checks_given,
+                prior_pockets: board.pockets,
+                prior_promoted_pieces: board.promoted_pieces,
+            };
+            board.position_history.push(board.zobrist_hash);
+            let is_capture = self
+                .is_square_occupied_by_color(destination, Piece::get_opposite_color(piece.color));
+            if is_capture {
+                //is capture
+                if let Some(captured_piece) = self.get_occupant(destination) {
+                    //capture piece by clearing destination on captured piece's bitboard
+                    let captured_piece_bitboard = &mut self.all_pieces[captured_piece.to_index()];
+                    *captured_piece_bitboard &= !(1u64 << destination);
+                    move_record.captured_piece = Some(captured_piece);
+                }
+            }
+            if piece.group == PieceGroup::Pawn
+                && self.en_passant & Bitboards::convert_to_bit(destination) != 0
+            {
+                //is en passant
+                let captured_pawn_square = match piece.color {
+                    PieceColor::White => destination - 8,
+                    PieceColor::Black => destination + 8,
+                };
+                let captured_pawn_bitboard = &mut self.all_pieces[Piece::to_piece_index(
+                    Piece::get_opposite_color(piece.color),
+                    PieceGroup::Pawn,
+                )];
+
+                //clear square behind en passant destination
+                *captured_pawn_bitboard &= !(1u64 << captured_pawn_square);
+                board.update_square(captured_pawn_square, None);
+                move_record.captured_piece = Some(Piece {
+                    group: PieceGroup::Pawn,
+                    color: Piece::get_opposite_color(piece.color),
+                    bitboard: 0u64,
+                });
+                move_record.captured_square = captured_pawn_square;
+            }
+
+            if board.variant.has_drops() {
+                //captured pieces go to the capturer's pocket, promoted ones as the pawns they were
+                let captured_bit = Bitboards::convert_to_bit(move_record.captured_square);
+                if let Some(captured_piece) = move_record.captured_piece {
+                    let pocket_group = match board.promoted_pieces & captured_bit != 0 {
+                        true => PieceGroup::Pawn,
+                        false => captured_piece.group,
+                    };
+                    let count = board.get_pocket_count(piece.color, pocket_group);
+                    board.update_pocket(piece.color, pocket_group, count + 1);
+                }
+                let origin_bit = Bitboards::convert_to_bit(origin);
+                let is_promoted = board.promoted_pieces & origin_bit != 0;
+                let mut promoted_pieces = board.promoted_pieces & !(origin_bit | captured_bit);
+                if is_promoted {
+                    promoted_pieces |= Bitboards::convert_to_bit(destination);
+                }
+                board.update_promoted_pieces(promoted_pieces);
+            }
+
+            //halfmove clock resets on pawn moves and captures
+            board.update_move_counters(piece.color, piece.group == PieceGroup::Pawn || is_capture);
+
+            //calculate en passants for next move, previous en passant square expires
+            self.update_en_passant(board, None);
+            if piece.group == PieceGroup::Pawn && origin.abs_diff(destination) == 8 * 2 {
+                let skipped_square = (origin + destination) / 2;
+                let enemy_pawns = self.all_pieces[Piece::to_piece_index(
+                    Piece::get_opposite_color(piece.color),
+                    PieceGroup::Pawn,
+                )];
+                let adjacent_squares = [destination.checked_sub(1), Some(destination + 1)]
+                    .into_iter()
+                    .flatten()
+                    .filter(|square| square / 8 == destination / 8);
+
+                for adjacent_square in adjacent_squares {
+                    if enemy_pawns & Bitboards::convert_to_bit(adjacent_square) != 0 {
+                        //destination of possible en passanting pawn
+                        self.update_en_passant(board, Some(skipped_square));
+                    }
+                }
+            }
+
+            //can't castle if king or rook has moved, or once the rook is captured on its starting square
+            for color in [PieceColor::White, PieceColor::Black] {
+                for side in [PieceGroup::King, PieceGroup::Queen] {
+                    let rook_square = board.get_castling_rook_square(color, side);
+                    let is_king_move = piece.group == PieceGroup::King && piece.color == color;
+                    if is_king_move || origin == rook_square || destination == rook_square {
+                        board.update_can_castle(color, side, false);
+                    }
+                }
+            }
+
+            //move piece by updating origin and destination on piece's bitboard
+            let king_destination = match castling_side {
+                Some(side) => {
+                    //lift both pieces before placing either, the king and rook may swap squares
+                    let (king_destination, rook_destination) =
+                        Bitboards::get_castled_squares(piece.color, side);
+                    let rook_origin = board.get_castling_rook_square(piece.color, side);
+                    let rook = Piece {
+                        group: PieceGroup::Rook,
+                        color: piece.color,
+                        bitboard: 0u64,
+                    };
+                    self.all_pieces[rook.to_index()] &= !(1u64 << rook_origin);
+                    board.update_square(rook_origin, None);
+                    self.all_pieces[rook.to_index()] |= 1u64 << rook_destination;
+                    board.update_square(rook_destination, Some(rook));
+                    move_record.castling_rook = Some((rook_origin, rook_destination));
+                    king_destination
+                }
+                None => destination,
+            };
+            let bitboard = &mut self.all_pieces[piece.to_index()];
+            *bitboard = (*bitboard & !(1u64 << origin)) | (1u64 << king_destination);
+            if board.squares[origin as usize] == Some(piece) {
+                board.update_square(origin, None);
+            }
+            board.update_square(king_destination, Some(piece));
+
+            if board.variant.has_explosions() && move_record.captured_piece.is_some() {
+                self.explode(board, destination, &mut move_record);
+            }
+
+            //reset checking_pieces, may not be necessary
+            self.checking_pieces = [0u64, 0u64];
+            Ok(move_record)
+        } else {
+            Err(MoveError::NoPieceOnOrigin(origin))
+        }
+    }
+
+    pub fn promote_pawn(
+        &mut self,
+        board: &mut Board,
+        origin: u8,
+        destination: u8,
+        promotion: PieceGroup,
+    ) -> Result<MoveRecord, MoveError> {
+        self.validate_promotion(board, origin, destination, Some(promotion))?;
+        let mut move_record = self.relocate_piece(board, origin, destination)?;
+
+        //clear destination from pawn bitboard
+        //add destination to promotion bitboard
+        //update_square on board for destination to promotion piece
+        let pawn = move_record.piece;
+        move_record.promotion = Some(promotion);
+        if board.variant.has_explosions() && move_record.captured_piece.is_some() {
+            //the pawn exploded along with the piece it took
+            return Ok(move_record);
+        }
+        let pawn_bitboard = &mut self.all_pieces[pawn.to_index()];
+        *pawn_bitboard &= !(1u64 << destination);
+
+        let promotion_bitboard = &mut self.all_pieces[Piece::to_piece_index(pawn.color, promotion)];
+        *promotion_bitboard |= 1u64 << destination;
+
+        board.update_square(
+            destination,
+            Some(Piece {
+                group: promotion,
+                color: pawn.color,
+                bitboard: 0u64,
+            }),
+        );
+        if board.variant.has_drops() {
+            board.update_promoted_pieces(board.promoted_pieces | 1u64 << destination);
+        }
+        Ok(move_record)
+    }
+
+    ///Puts a piece from the side to move's pocket on the empty destination, the drop must not leave the king in check.
+    pub fn drop_piece(
+        &mut self,
+        board: &mut Board,
+        group: PieceGroup,
+        destination: u8,
+    ) -> Result<MoveRecord, MoveError> {
+        let color = board.turn_color;
+        let count = board.get_pocket_count(color, group);
+        if !board.variant.has_drops() || count == 0 {
+            return Err(MoveError::PieceNotInPocket(group));
+        }
+        if destination > 63 {
+            return Err(MoveError::IllegalDestination(destination));
+        }
+        let destination_bit = Bitboards::convert_to_bit(destination);
+        if self.get_all_pieces_on_one_bitboard() & destination_bit != 0
+            || (group == PieceGroup::Pawn && BACK_RANKS & destination_bit != 0)
+        {
+            return Err(MoveError::IllegalDestination(destination));
+        }
+        if self.get_drop_destinations(board, group, true) & destination_bit == 0 {
+            return Err(MoveError::KingLeftInCheck);
+        }
+
+        let piece = Piece {
+            group,
+            color,
+            bitboard: 0u64,
+        };
+        let move_record = MoveRecord {
+            piece,
+            origin: destination,
+            destination,
+            captured_piece: None,
+            captured_square: destination,
+            promotion: None,
+            drop: Some(group),
+            castling_rook: None,
+            exploded_pieces: [None; 8],
+            prior_can_kingside_castle: board.can_kingside_castle,
+            prior_can_queenside_castle: board.can_queenside_castle,
+            prior_en_passant: self.get_en_passant_square(),
+            prior_halfmove_clock: board.halfmove_clock,
+            prior_fullmove_number: board.fullmove_number,
+            prior_checks_given: board.checks_given,
+            prior_pockets: board.pockets,
+            prior_promoted_pieces: board.promoted_pieces,
+        };
+        board.position_history.push(board.zobrist_hash);
+        board.update_pocket(color, group, count - 1);
+        self.all_pieces[piece.to_index()] |= destination_bit;
+        board.update_square(destination, Some(piece));
+
+        //pawn drops reset the halfmove clock like any pawn move
+        board.update_move_counters(color, group == PieceGroup::Pawn);
+        self.update_en_passant(board, None);
+        self.checking_pieces = [0u64, 0u64];
+        Ok(move_record)
+    }
+
+    ///Takes the piece that captured on square and every piece but pawns around it off the board, recording them for unmake_move.
+    fn explode(&mut self, board: &mut Board, square: u8, move_record: &mut MoveRecord) {
+        self.all_pieces[move_record.piece.to_index()] &= !Bitboards::convert_to_bit(square);
+        board.update_square(square, None);
+
+        let mut blast = self.get_blast_squares(square);
+        let mut exploded_index = 0;
+        while blast != 0 {
+            let exploded_square = blast.trailing_zeros() as u8;
+            blast &= blast - 1;
+            let Some(exploded_piece) = self.get_occupant(exploded_square) else {
+                continue;
+            };
+            self.all_pieces[exploded_piece.to_index()] &=
+                !Bitboards::convert_to_bit(exploded_square);
+            board.update_square(exploded_square, None);
+            move_record.exploded_pieces[exploded_index] = Some((exploded_square, exploded_piece));
+            exploded_index += 1;
+
+            //an exploded king or castling rook takes its castling rights with it
+            for side in [PieceGroup::King, PieceGroup::Queen] {
+                if exploded_piece.group == PieceGroup::King
+                    || exploded_square == board.get_castling_rook_square(exploded_piece.color, side)
+                {
+                    board.update_can_castle(exploded_piece.color, side, false);
+                }
+            }
+        }
+    }
+
+    ///Gets the squares around an atomic capture on square whose pieces explode, pawns survive.
+    fn get_blast_squares(&self, square: u8) -> u64 {
+        let pawns = self.all_pieces[Piece::to_piece_index(PieceColor::White, PieceGroup::Pawn)]
+            | self.all_pieces[Piece::to_piece_index(PieceColor::Black, PieceGroup::Pawn)];
+        attacks::get_king_attacks(square) & self.get_all_pieces_on_one_bitboard() & !pawns
+    }
+
+    ///Checks that a promotion is given exactly when a pawn reaches the last rank, and that it names a piece a pawn may become in the variant.
+    fn validate_promotion(
+        &self,
+        board: &Board,
+        origin: u8,
+        destination: u8,
+        promotion: Option<PieceGroup>,
+    ) -> Result<(), MoveError> {
+        let piece = self
+            .get_occupant(origin)
+            .ok_or(MoveError::NoPieceOnOrigin(origin))?;
+
+        match (Bitboards::is_promotion_move(piece, destination), promotion) {
+            (true, None) => Err(MoveError::PromotionRequired),
+            (false, Some(_)) => Err(MoveError::PromotionNotAllowed),
+            (true, Some(group)) if !board.variant.get_promotion_groups().contains(&group) => {
+                Err(MoveError::InvalidPromotionPiece(group))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    ///Checks if moving piece to destination is a pawn reaching its last rank.
+    pub fn is_promotion_move(piece: Piece, destination: u8) -> bool {
+        let last_rank = match piece.color {
+            PieceColor::White => 7,
+            PieceColor::Black => 0,
+        };
+        piece.group == PieceGroup::Pawn && destination / 8 == last_rank
+    }
+
+    ///Gets, for each origin, the legal destinations that are promotions and need a piece choice.
+    pub fn get_promotion_moves(&self, legal_moves: &[u64]) -> Vec<u64> {
+        (0..64u8)
+            .zip(legal_moves)
+            .map(|(origin, moves)| match self.get_occupant(origin) {
+                Some(piece) if piece.group == PieceGroup::Pawn => {
+                    let last_rank = match piece.color {
+                        PieceColor::White => 0xFF00_0000_0000_0000u64,
+                        PieceColor::Black => 0x0000_0000_0000_00FFu64,
+                    };
+                    moves & last_rank
+                }
+                _ => 0u64,
+            })
+            .collect()
+    }
+
+    ///Reverses a move made by move_piece, promote_pawn or drop_piece, restoring the position exactly as it was before.
+    pub fn unmake_move(&mut self, board: &mut Board, move_record: &MoveRecord) {
+        let piece = move_record.piece;
+        let moved_group = move_record.promotion.unwrap_or(piece.group);
+
+        //a castled king stands beside its rook rather than on the destination
+        let landing_square = match move_record.castling_rook {
+            Some((_, rook_destination)) => {
+                let side = match rook_destination % 8 {
+                    5 => PieceGroup::King,
+                    _ => PieceGroup::Queen,
+                };
+                Bitboards::get_castled_squares(piece.color, side).0
+            }
+            None => move_record.destination,
+        };
+
+        //lift the moved piece and any castled rook before putting either back, they may have swapped squares
+        self.all_pieces[Piece::to_piece_index(piece.color, moved_group)] &=
+            !(1u64 << landing_square);
+        board.update_square(landing_square, None);
+        if let Some((rook_origin, rook_destination)) = move_record.castling_rook {
+            let rook = Piece {
+                group: PieceGroup::Rook,
+                color: piece.color,
+                bitboard: 0u64,
+            };
+            self.all_pieces[rook.to_index()] &= !(1u64 << rook_destination);
+            board.update_square(rook_destination, None);
+            self.all_pieces[rook.to_index()] |= 1u64 << rook_origin;
+            board.update_square(rook_origin, Some(rook));
+        }
+
+        //move piece back, demoting it if it was promoted, a dropped piece goes back to the pocket below
+        if move_record.drop.is_none() {
+            self.all_pieces[piece.to_index()] |= 1u64 << move_record.origin;
+            board.update_square(move_record.origin, Some(piece));
+        }
+
+        //restore captured piece, which is behind the destination for en passant
+        if let Some(captured_piece) = move_record.captured_piece {
+            self.all_pieces[captured_piece.to_index()] |= 1u64 << move_record.captured_square;
+            board.update_square(move_record.captured_square, Some(captured_piece));
+        }
+        for (exploded_square, exploded_piece) in move_record.exploded_pieces.into_iter().flatten() {
+            self.all_pieces[exploded_piece.to_index()] |= 1u64 << exploded_square;
+            board.update_square(exploded_square, Some(exploded_piece));
+        }
+
+        for color in [PieceColor::White, PieceColor::Black] {
+            let color_index = Piece::color_to_index(color);
+            board.update_can_castle(
+                color,
+                PieceGroup::King,
+                move_record.prior_can_kingside_castle[color_index],
+            );
+            board.update_can_castle(
+                color,
+                PieceGroup::Queen,
+                move_record.prior_can_queenside_castle[color_index],
+            );
+        }
+        board.halfmove_clock = move_record.prior_halfmove_clock;
+        board.fullmove_number = move_record.prior_fullmove_number;
+        for color in [PieceColor::White, PieceColor::Black] {
+            board.update_checks_given(
+                color,
+                move_record.prior_checks_given[Piece::color_to_index(color)],
+            );
+        }
+        for color in [PieceColor::White, PieceColor::Black] {
+            for (group, count) in POCKET_GROUPS
+                .into_iter()
+                .zip(move_record.prior_pockets[Piece::color_to_index(color)])
+            {
+                board.update_pocket(color, group, count);
+            }
+        }
+        board.update_promoted_pieces(move_record.prior_promoted_pieces);
+        board.position_history.pop();
+        self.update_en_passant(board, move_record.prior_en_passant);
+        self.checking_pieces = [0u64, 0u64];
+    }
+
+    ///Sets the en passant square, keeping the board's hash in step.
+    fn update_en_passant(&mut self, board: &mut Board, en_passant: Option<u8>) {
+        if let Some(square) = self.get_en_passant_square() {
+            board.zobrist_hash ^= ZOBRIST_KEYS.en_passant_key(square);
+        }
+        if let Some(square) = en_passant {
+            board.zobrist_hash ^= ZOBRIST_KEYS.en_passant_key(square);
+        }
+        self.en_passant = en_passant.map_or(
+            Bitboards::create_empty_bitboard(),
+            Bitboards::convert_to_bit,
+        );
+    }
+
+    /* Bitboard Initialization */
+
+    fn create_piece_bitboards() -> PieceBitboards {
+        Piece::initialize_all_pieces().map(Bitboards::create_bitboard_for_piece)
+    }
+
+    fn create_empty_bitboard() -> u64 {
+        0u64
+    }
+
+    fn create_bitboard_for_piece(piece: Piece) -> u64 {
+        match piece.group {
+            PieceGroup::Pawn => Bitboards::create_pawn_bitboard(piece.color),
+            PieceGroup::Rook => Bitboards::create_rook_bitboard(piece.color),
+            PieceGroup::Knight => Bitboards::create_knight_bitboard(piece.color),
+            PieceGroup::Bishop => Bitboards::create_bishop_bitboard(piece.color),
+            PieceGroup::Queen => Bitboards::create_queen_bitboard(piece.color),
+            PieceGroup::King => Bitboards::create_king_bitboard(piece.color),
+        }
+    }
+
+    fn create_bitboard_for_positions(positions: Vec<Positions>) -> u64 {
+        let mut bitboard: u64 = 0;
+        for position in positions {
+            bitboard |= 1u64 << position.to_index();
+        }
+        bitboard
+    }
+
+    fn create_pawn_bitboard(color: PieceColor) -> u64 {
+        match color {
+            PieceColor::White => Bitboards::create_bitboard_for_positions(vec![
+                Positions::A2,
+                Positions::B2,
+                Positions::C2,
+                Positions::D2,
+                Positions::E2,
+                Positions::F2,
+                Positions::G2,
+                Positions::H2,
+            ]),
+            PieceColor::Black => Bitboards::create_bitboard_for_positions(vec![
+                Positions::A7,
+                Positions::B7,
+                Positions::C7,
+                Positions::D7,
+                Positions::E7,
+                Positions::F7,
+                Positions::G7,
+                Positions::H7,
+            ]),
+        }
+    }
+
+    fn create_rook_bitboard(color: PieceColor) -> u64 {
+        match color {
+            PieceColor::White => {
+                Bitboards::create_bitboard_for_positions(vec![Positions::A1, Positions::H1])
+            }
+            PieceColor::Black => {
+                Bitboards::create_bitboard_for_positions(vec![Positions::A8, Positions::H8])
+            }
+        }
+    }
+
+    fn create_knight_bitboard(color: PieceColor) -> u64 {
+        match color {
+            PieceColor::White => {
+                Bitboards::create_bitboard_for_positions(vec![Positions::B1, Positions::G1])
+            }
+            PieceColor::Black => {
+                Bitboards::create_bitboard_for_positions(vec![Positions::B8, Positions::G8])
+            }
+        }
+    }
+
+    fn create_bishop_bitboard(color: PieceColor) -> u64 {
+        match color {
+            PieceColor::White => {
+                Bitboards::create_bitboard_for_positions(vec![Positions::C1, Positions::F1])
+            }
+            PieceColor::Black => {
+                Bitboards::create_bitboard_for_positions(vec![Positions::C8, Positions::F8])
+            }
+        }
+    }
+
+    fn create_queen_bitboard(color: PieceColor) -> u64 {
+        match color {
+            PieceColor::White => Bitboards::create_bitboard_for_positions(vec![Positions::D1]),
+            PieceColor::Black => Bitboards::create_bitboard_for_positions(vec![Positions::D8]),
+        }
+    }
+
+    fn create_king_bitboard(color: PieceColor) -> u64 {
+        match color {
+            PieceColor::White => Bitboards::create_bitboard_for_positions(vec![Positions::E1]),
+            PieceColor::Black => Bitboards::create_bitboard_for_positions(vec![Positions::E8]),
+        }
+    }
+
+    /* Legal Move Calculations */
+
+    ///Gets the destinations the piece moves to by its movement rules alone, ignoring pins and checks.
+    fn get_pseudo_legal_moves(&self, board: &Board, origin: u8, piece: Piece) -> u64 {
+        match piece.group {
+            PieceGroup::Pawn => self.get_pawn_moves(origin, piece),
+            PieceGroup::Rook => self.get_sliding_moves(origin, piece),
+            PieceGroup::Knight => self.get_knight_moves(origin, piece),
+            PieceGroup::Bishop => self.get_sliding_moves(origin, piece),
+            PieceGroup::Queen => self.get_sliding_moves(origin, piece),
+            //atomic kings can't capture, they would explode themselves
+            PieceGroup::King if board.variant.has_explosions() => {
+                (self.get_king_moves(origin, piece) & !self.get_all_pieces_on_one_bitboard())
+                    | self.get_castling_moves(board, origin, piece)
+            }
+            PieceGroup::King => {
+                self.get_king_moves(origin, piece) | self.get_castling_moves(board, origin, piece)
+            }
+        }
+    }
+
+    fn is_square_defended(&self, square: u8, defending_color: PieceColor) -> bool {
+        let defending_bitboard = self.attacks[Piece::color_to_index(defending_color)];
+        let combined_bitboard = Bitboards::convert_to_bit(square) & defending_bitboard;
+
+        combined_bitboard != 0
+    }
+
+    fn get_pawn_moves(&self, origin: u8, piece: Piece) -> u64 {
+        //pawns never stand on their last rank, so one step forward stays on the board
+        let (one_forward, two_forward, starting_rank) = match piece.color {
+            PieceColor::White => (origin + 8, origin + 16, 1),
+            PieceColor::Black => (origin - 8, origin.wrapping_sub(16), 6),
+        };
+        let occupancy = self.get_all_pieces_on_one_bitboard();
+        let mut possible_moves = Bitboards::create_empty_bitboard();
+
+        if occupancy & Bitboards::convert_to_bit(one_forward) == 0 {
+            possible_moves |= Bitboards::convert_to_bit(one_forward);
+
+            if origin / 8 == starting_rank
+                && occupancy & Bitboards::convert_to_bit(two_forward) == 0
+            {
+                possible_moves |= Bitboards::convert_to_bit(two_forward);
+            }
+        }
+
+        //is capture or en passant
+        possible_moves |= attacks::get_pawn_attacks(piece.color, origin)
+            & (self.get_color_occupancy(Piece::get_opposite_color(piece.color)) | self.en_passant);
+
+        possible_moves
+    }
+
+    fn get_sliding_moves(&self, origin: u8, piece: Piece) -> u64 {
+        Bitboards::get_sliding_attacks(piece.group, origin, self.get_all_pieces_on_one_bitboard())
+            & !self.get_color_occupancy(piece.color)
+    }
+
+    fn get_knight_moves(&self, origin: u8, piece: Piece) -> u64 {
+        attacks::get_knight_attacks(origin) & !self.get_color_occupancy(piece.color)
+    }
+
+    fn get_king_moves(&self, origin: u8, piece: Piece) -> u64 {
+        //stepping onto attacked squares is left to is_valid_move
+        attacks::get_king_attacks(origin) & !self.get_color_occupancy(piece.color)
+    }
+
+    ///Gets the castling moves, which are fully checked here since the king may land on its own rook's square in Chess960.
+    fn get_castling_moves(&self, board: &Board, origin: u8, piece: Piece) -> u64 {
+        let enemy_color = Piece::get_opposite_color(piece.color);
+        let enemy_king = self.all_pieces[Piece::to_piece_index(enemy_color, PieceGroup::King)];
+        //nothing can attack the squares beside the enemy king in atomic, a capture there would explode it
+        let safe_squares = match board.variant.has_explosions() && enemy_king != 0 {
+            true => attacks::get_king_attacks(enemy_king.trailing_zeros() as u8),
+            false => Bitboards::create_empty_bitboard(),
+        };
+        let enemy_attacks = self.attacks[Piece::color_to_index(enemy_color)] & !safe_squares;
+        let rook_bitboard = self.all_pieces[Piece::to_piece_index(piece.color, PieceGroup::Rook)];
+        let enemy_rank_sliders = self.all_pieces
+            [Piece::to_piece_index(enemy_color, PieceGroup::Rook)]
+            | self.all_pieces[Piece::to_piece_index(enemy_color, PieceGroup::Queen)];
+        let mut possible_moves = Bitboards::create_empty_bitboard();
+
+        for side in [PieceGroup::King, PieceGroup::Queen] {
+            if !board.can_castle(piece.color, side) {
+                continue;
+            }
+            let rook_origin = board.get_castling_rook_square(piece.color, side);
+            let (king_destination, rook_destination) =
+                Bitboards::get_castled_squares(piece.color, side);
+            if rook_bitboard & Bitboards::convert_to_bit(rook_origin) == 0
+                || origin / 8 != king_destination / 8
+            {
+                continue;
+            }
+
+            let king_path = Bitboards::get_ray_bitboard(origin, king_destination)
+                | Bitboards::convert_to_bit(origin)
+                | Bitboards::convert_to_bit(king_destination); //inclusive
+            let rook_path = Bitboards::get_ray_bitboard(rook_origin, rook_destination)
+                | Bitboards::convert_to_bit(rook_destination);
+            let castling_pieces =
+                Bitboards::convert_to_bit(origin) | Bitboards::convert_to_bit(rook_origin);
+            let occupancy = self.get_all_pieces_on_one_bitboard() & !castling_pieces;
+
+            if (king_path | rook_path) & occupancy != 0 || king_path & enemy_attacks != 0 {
+                //only king and rook may be in castling squares
+                //no enemy attacks may hit the squares the king stands on, crosses or lands on
+                continue;
+            }
+            if attacks::get_rook_attacks(king_destination, occupancy) & enemy_rank_sliders != 0
+                && safe_squares & Bitboards::convert_to_bit(king_destination) == 0
+            {
+                //the castling rook was shielding the king's destination along the first rank
+                continue;
+            }
+
+            possible_moves |= Bitboards::convert_to_bit(match board.is_chess960 {
+                true => rook_origin,
+                false => king_destination,
+            });
+        }
+
+        possible_moves
+    }
+
+    ///Gets the castling side if the king's move from origin to destination is a castle.
+    pub fn get_castling_side(
+        &self,
+        board: &Board,
+        origin: u8,
+        destination: u8,
+    ) -> Option<PieceGroup> {
+        let piece = self.get_occupant(origin)?;
+        if piece.group != PieceGroup::King {
+            return None;
+        }
+        [PieceGroup::King, PieceGroup::Queen]
+            .into_iter()
+            .find(|side| {
+                board.can_castle(piece.color, *side)
+                    && destination == Bitboards::get_castling_destination(board, piece.color, *side)
+            })
+    }
+
+    ///Gets the square a castling king is moved to, its own rook's square in Chess960.
+    pub fn get_castling_destination(board: &Board, color: PieceColor, side: PieceGroup) -> u8 {
+        match board.is_chess960 {
+            true => board.get_castling_rook_square(color, side),
+            false => Bitboards::get_castled_squares(color, side).0,
+        }
+    }
+
+    ///Gets where the king and rook end up after castling, the g and f files kingside and the c and d files queenside.
+    fn get_castled_squares(color: PieceColor, side: PieceGroup) -> (u8, u8) {
+        let back_rank_start = Board::get_back_rank_start(color);
+        match side {
+            PieceGroup::King => (back_rank_start + 6, back_rank_start + 5),
+            _ => (back_rank_start + 2, back_rank_start + 3),
+        }
+    }
+
+    fn create_legal_moves_bitboard(
+        &self,
+        board: &Board,
+        piece: Piece,
+        possible_moves: u64,
+        origin: u8,
+    ) -> u64 {
+        let piece_bitboard = self.all_pieces[piece.to_index()];
+        //castling moves are already legal and may land on the king's own rook
+        let castling_moves = match piece.group {
+            PieceGroup::King => possible_moves & self.get_castling_moves(board, origin, piece),
+            _ => Bitboards::create_empty_bitboard(),
+        };
+        let mut moves_bitboard = castling_moves;
+
+        let mut destinations = possible_moves & !castling_moves;
+        while destinations != 0 {
+            let destination = destinations.trailing_zeros() as u8;
+            destinations &= destinations - 1;
+
+            if self
+                .is_valid_move(board.variant, origin, destination, &piece_bitboard, piece)
+                .is_ok()
+            {
+                moves_bitboard |= Bitboards::convert_to_bit(destination);
+            }
+        }
+
+        //return bitboard with 1s in all legal move positions
+        moves_bitboard
+    }
+
+    /* Attack Calculations */
+
+    fn get_attacks(&mut self, origin: u8, piece: Piece) -> u64 {
+        let enemy_king = self.all_pieces
+            [Piece::to_piece_index(Piece::get_opposite_color(piece.color), PieceGroup::King)];
+        let attacks = match piece.group {
+            PieceGroup::Pawn => attacks::get_pawn_attacks(piece.color, origin),
+            PieceGroup::Knight => attacks::get_knight_attacks(origin),
+            PieceGroup::King => attacks::get_king_attacks(origin),
+            //the enemy king doesn't block, it can't step back along the attacking ray
+            _ => Bitboards::get_sliding_attacks(
+                piece.group,
+                origin,
+                self.get_all_pieces_on_one_bitboard() & !enemy_king,
+            ),
+        };
+
+        if attacks & enemy_king != 0 {
+            //is checker
+            self.checking_pieces[Piece::color_to_index(piece.color)] |=
+                Bitboards::convert_to_bit(origin);
+        }
+        attacks
+    }
+
+    fn get_sliding_attacks(group: PieceGroup, origin: u8, occupancy: u64) -> u64 {
+        match group {
+            PieceGroup::Rook => attacks::get_rook_attacks(origin, occupancy),
+            PieceGroup::Bishop => attacks::get_bishop_attacks(origin, occupancy),
+            PieceGroup::Queen => attacks::get_queen_attacks(origin, occupancy),
+            _ => Bitboards::create_empty_bitboard(),
+        }
+    }
+
+    /* Helper Methods */
+
+    fn get_occupant(&self, square: u8) -> Option<Piece> {
+        let square_bitboard = Bitboards::convert_to_bit(square);
+        self.all_pieces
+            .iter()
+            .position(|piece_bitboard| piece_bitboard & square_bitboard != 0)
+            .and_then(Piece::from_index)
+    }
+
+    ///Checks if the color's king is attacked, attacks must be current from get_all_legal_moves or get_all_attacks.
+    pub fn is_checked(&self, color: PieceColor) -> bool {
+        let king_bitboard = match color {
+            PieceColor::White => {
+                self.all_pieces[Piece {
+                    group: PieceGroup::King,
+                    color: PieceColor::White,
+                    bitboard: 0u64,
+                }
+                .to_index()]
+            }
+            PieceColor::Black => {
+                self.all_pieces[Piece {
+                    group: PieceGroup::King,
+                    color: PieceColor::Black,
+                    bitboard: 0u64,
+                }
+                .to_index()]
+            }
+        };
+        let enemy_king = self.all_pieces
+            [Piece::to_piece_index(Piece::get_opposite_color(color), PieceGroup::King)];
+        if king_bitboard != 0
+            && attacks::get_king_attacks(king_bitboard.trailing_zeros() as u8) & enemy_king != 0
+        {
+            //kings side by side can't check each other, only possible in atomic where the capture would explode both
+            return false;
+        }
+        self.attacks[Piece::color_to_index(Piece::get_opposite_color(color))] & king_bitboard != 0
+    }
+
+    fn get_all_pieces_on_one_bitboard(&self) -> u64 {
+        self.all_pieces
+            .iter()
+            .fold(Bitboards::create_empty_bitboard(), |occupancy, bitboard| {
+                occupancy | bitboard
+            })
+    }
+
+    fn get_color_occupancy(&self, color: PieceColor) -> u64 {
+        let color_pieces = match color {
+            PieceColor::White => &self.all_pieces[0..6],
+            PieceColor::Black => &self.all_pieces[6..12],
+        };
+        color_pieces
+            .iter()
+            .fold(Bitboards::create_empty_bitboard(), |occupancy, bitboard| {
+                occupancy | bitboard
+            })
+    }
+
+    /// Checks the destination is reachable as validate_destination does, then that the move doesn't leave the own king in check.
+    fn is_valid_move(
+        &self,
+        variant: Variant,
+        origin: u8,
+        destination: u8,
+        origin_bitboard: &u64,
+        piece: Piece,
+    ) -> Result<(), MoveError> {
+        self.validate_destination(origin, destination, origin_bitboard, piece)?;
+        if variant.has_explosions() {
+            //explosions can remove pinning and checking pieces, so the position after the move is checked instead
+            return self.validate_explosions(origin, destination, piece);
+        }
+        if !self.validate_pins(piece, origin, destination) {
+            //pinned piece tries to move out of pin
+            return Err(MoveError::PinnedPiece);
+        }
+        if !self.validate_checks(piece, destination) {
+            //if king is in check, this is a non-king move that neither captures nor blocks a checking piece
+            return Err(MoveError::KingLeftInCheck);
+        }
+
+        if piece.group == PieceGroup::King
+            && self.is_square_defended(destination, Piece::get_opposite_color(piece.color))
+        {
+            //king move is to defended square
+            return Err(MoveError::KingLeftInCheck);
+        }
+
+        Ok(())
+    }
+
+    /// Checks if origin & destination are within bounds, if origin is occupied by given piece, if destination is not occupied by same color.
+    fn validate_destination(
+        &self,
+        origin: u8,
+        destination: u8,
+        origin_bitboard: &u64,
+        piece: Piece,
+    ) -> Result<(), MoveError> {
+        if origin > 63 {
+            //out of bounds
+            return Err(MoveError::NoPieceOnOrigin(origin));
+        }
+        if destination > 63 {
+            //out of bounds
+            return Err(MoveError::IllegalDestination(destination));
+        }
+        if Bitboards::convert_to_bit(origin) & *origin_bitboard == 0 {
+            //piece to move's bitboard doesn't have piece at origin
+            return Err(MoveError::NoPieceOnOrigin(origin));
+        }
+        if self.is_square_occupied_by_color(destination, piece.color) {
+            //destination is occupied by same colored piece
+            return Err(MoveError::IllegalDestination(destination));
+        }
+        Ok(())
+    }
+
+    ///Checks an atomic move keeps the own king on the board and, unless the enemy king explodes or stands beside it, out of check.
+    fn validate_explosions(
+        &self,
+        origin: u8,
+        destination: u8,
+        piece: Piece,
+    ) -> Result<(), MoveError> {
+        let captured_square = match self.is_en_passant_capture(piece, destination) {
+            true => Bitboards::get_en_passant_captured_square(piece.color, destination),
+            false => destination,
+        };
+        let is_capture = self
+            .is_square_occupied_by_color(captured_square, Piece::get_opposite_color(piece.color));
+        if is_capture && piece.group == PieceGroup::King {
+            //kings can't capture, they would explode themselves
+            return Err(MoveError::IllegalDestination(destination));
+        }
+
+        let mut pieces_after = self.all_pieces;
+        pieces_after[piece.to_index()] &= !Bitboards::convert_to_bit(origin);
+        match is_capture {
+            true => {
+                let exploded = Bitboards::convert_to_bit(captured_square)
+                    | self.get_blast_squares(destination);
+                for pieces in pieces_after.iter_mut() {
+                    *pieces &= !exploded;
+                }
+            }
+            false => pieces_after[piece.to_index()] |= Bitboards::convert_to_bit(destination),
+        }
+
+        match Bitboards::is_king_safe_after_explosions(&pieces_after, piece.color) {
+            true => Ok(()),
+            false => Err(MoveError::KingLeftInCheck),
+        }
+    }
+
+    ///Checks the color's king survived and can't be captured in the atomic position held by pieces, exploding the enemy king wins regardless.
+    fn is_king_safe_after_explosions(pieces: &PieceBitboards, color: PieceColor) -> bool {
+        let enemy_color = Piece::get_opposite_color(color);
+        let enemy_pieces = |group: PieceGroup| pieces[Piece::to_piece_index(enemy_color, group)];
+        let king_bitboard = pieces[Piece::to_piece_index(color, PieceGroup::King)];
+        if king_bitboard == 0 {
+            return false;
+        }
+        let king_square = king_bitboard.trailing_zeros() as u8;
+        let enemy_king = enemy_pieces(PieceGroup::King);
+        if enemy_king == 0 || attacks::get_king_attacks(king_square) & enemy_king != 0 {
+            return true;
+        }
+
+        let occupancy = pieces
+            .iter()
+            .fold(Bitboards::create_empty_bitboard(), |occupancy, bitboard| {
+                occupancy | bitboard
+            });
+        let diagonal_sliders = enemy_pieces(PieceGroup::Bishop) | enemy_pieces(PieceGroup::Queen);
+        let straight_sliders = enemy_pieces(PieceGroup::Rook) | enemy_pieces(PieceGroup::Queen);
+        attacks::get_pawn_attacks(color, king_square) & enemy_pieces(PieceGroup::Pawn) == 0
+            && attacks::get_knight_attacks(king_square) & enemy_pieces(PieceGroup::Knight) == 0
+            && attacks::get_bishop_attacks(king_square, occupancy) & diagonal_sliders == 0
+            && attacks::get_rook_attacks(king_square, occupancy) & straight_sliders == 0
+    }
+
+    fn validate_checks(&self, piece: Piece, destination: u8) -> bool {
+        if self.is_checked(piece.color) && piece.group != PieceGroup::King {
+            //is check
+            let king_bitboard =
+                self.all_pieces[Piece::to_piece_index(piece.color, PieceGroup::King)];
+            let checkers_bitboard =
+                self.checking_pieces[Piece::color_to_index(Piece::get_opposite_color(piece.color))];
+            if checkers_bitboard.count_ones() > 1 {
+                //double check, only the king can move
+                return false;
+            }
+            let blocking_bitboard =
+                Bitboards::get_rays_from_bitboards(checkers_bitboard, king_bitboard);
+            let captured_square = match self.is_en_passant_capture(piece, destination) {
+                true => Bitboards::get_en_passant_captured_square(piece.color, destination),
+                false => destination,
+            };
+
+            if checkers_bitboard & Bitboards::convert_to_bit(captured_square) == 0
+                && blocking_bitboard & Bitboards::convert_to_bit(destination) == 0
+            {
+                //is non-king move that is neither capturing nor blocking a checking piece
+                return false;
+            }
+        }
+        true
+    }
+
+    fn validate_pins(&self, piece: Piece, origin: u8, destination: u8) -> bool {
+        let bishop_bb = self.all_pieces
+            [Piece::to_piece_index(Piece::get_opposite_color(piece.color), PieceGroup::Bishop)];
+        let rook_bb = self.all_pieces
+            [Piece::to_piece_index(Piece::get_opposite_color(piece.color), PieceGroup::Rook)];
+        let queen_bb = self.all_pieces
+            [Piece::to_piece_index(Piece::get_opposite_color(piece.color), PieceGroup::Queen)];
+
+        let king_bitboard = self.all_pieces[Piece::to_piece_index(piece.color, PieceGroup::King)];
+        let ray_destination = king_bitboard.trailing_zeros() as u8;
+        let mut occupancy = self.get_all_pieces_on_one_bitboard();
+        let is_en_passant = self.is_en_passant_capture(piece, destination);
+        if is_en_passant {
+            //en passant takes two pieces off their lines at once, so every line to the king is checked as it stands after the move
+            let captured_square =
+                Bitboards::get_en_passant_captured_square(piece.color, destination);
+            occupancy &=
+                !(Bitboards::convert_to_bit(origin) | Bitboards::convert_to_bit(captured_square));
+            occupancy |= Bitboards::convert_to_bit(destination);
+        }
+
+        for (group, mut sliding_bb) in [
+            (PieceGroup::Bishop, bishop_bb),
+            (PieceGroup::Rook, rook_bb),
+            (PieceGroup::Queen, queen_bb),
+        ] {
+            while sliding_bb != 0 {
+                let ray_origin = sliding_bb.trailing_zeros() as u8;
+                sliding_bb &= sliding_bb - 1;
+                if !Bitboards::is_on_sliding_line(group, ray_origin, ray_destination) {
+                    //slider can't attack along this line
+                    continue;
+                }
+                let pin_ray = Bitboards::get_ray_bitboard(ray_origin, ray_destination);
+                let obstructions = pin_ray & occupancy;
+                if is_en_passant {
+                    if obstructions == 0 {
+                        //nothing left between the slider and the king
+                        return false;
+                    }
+                    continue;
+                }
+
+                // only piece obstructing pin is current piece, valid pin
+                if obstructions == Bitboards::convert_to_bit(origin) {
+                    let legal_pin_ray = Bitboards::get_ray_bitboard(ray_origin, ray_destination)
+                        | (1u64 << ray_origin)
+                        | (1u64 << ray_destination);
+
+                    if Bitboards::convert_to_bit(destination) & legal_pin_ray == 0 {
+                        //trying to escape pin, invalid move
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    ///Checks if a slider of the given group on origin attacks along the line to destination, ignoring blockers.
+    fn is_on_sliding_line(group: PieceGroup, origin: u8, destination: u8) -> bool {
+        let file_diff = (origin % 8).abs_diff(destination % 8);
+        let rank_diff = (origin / 8).abs_diff(destination / 8);
+        let is_straight = file_diff == 0 || rank_diff == 0;
+        let is_diagonal = file_diff == rank_diff;
+
+        match group {
+            PieceGroup::Bishop => is_diagonal,
+            PieceGroup::Rook => is_straight,
+            _ => is_straight || is_diagonal,
+        }
+    }
+
+    fn is_en_passant_capture(&self, piece: Piece, destination: u8) -> bool {
+        piece.group == PieceGroup::Pawn
+            && self.en_passant & Bitboards::convert_to_bit(destination) != 0
+    }
+
+    ///Gets the square of the pawn taken by an en passant capture landing on destination.
+    fn get_en_passant_captured_square(color: PieceColor, destination: u8) -> u8 {
+        match color {
+            PieceColor::White => destination - 8,
+            PieceColor::Black => destination + 8,
+        }
+    }
+
+    fn get_rays_from_bitboards(origins: u64, destination: u64) -> u64 {
+        if destination.count_ones() != 1 {
+            //TODO: Handle this better
+
+            panic!(
+                "Destination bitboard must have exactly one bit set, received {:#?}",
+                destination as u8
+            );
+        }
+
+        let destination_index = destination.trailing_zeros() as u8;
+        let mut rays_bitboard = 0u64;
+
+        let mut origin_bits = origins;
+        while origin_bits != 0 {
+            let origin_index = origin_bits.trailing_zeros() as u8;
+            origin_bits &= origin_bits - 1; // clears the lowest/least significant bit
+
+            rays_bitboard |= Bitboards::get_ray_bitboard(origin_index, destination_index);
+        }
+
+        rays_bitboard
+    }
+
+    fn get_ray_bitboard(origin: u8, destination: u8) -> u64 {
+        let from_file = origin as i8 % 8;
+        let to_file = destination as i8 % 8;
+        let from_rank = origin as i8 / 8;
+        let to_rank = destination as i8 / 8;
+        let file_diff = to_file - from_file;
+        let rank_diff = to_rank - from_rank;
+        let (file_step, rank_step) = match (file_diff, rank_diff) {
+            (0, _) => (0, rank_diff.signum()), // vertical
+            (_, 0) => (file_diff.signum(), 0), // horizontal
+            _ if file_diff.abs() == rank_diff.abs() => (
+                // diagonal
+                file_diff.signum(),
+                rank_diff.signum(),
+            ),
+            _ => return 0, // invalid
+        };
+
+        let mut bitboard = 0u64;
+        let mut current_rank = from_rank + rank_step;
+        let mut current_file = from_file + file_step;
+
+        //while not at destination
+        while current_rank != to_rank || current_file != to_file {
+            let square = (current_rank * 8 + current_file) as u8;
+            bitboard |= 1u64 << square;
+
+            current_rank += rank_step;
+            current_file += file_step;
+        }
+
+        bitboard
+    }
+
+    fn is_square_occupied_by_color(&self, square: u8, piece_color: PieceColor) -> bool {
+        self.get_color_occupancy(piece_color) & Bitboards::convert_to_bit(square) != 0
+    }
+
+    pub fn convert_to_bit(num: u8) -> u64 {
+        1u64 << num
+    }
+}
+
+impl Default for Bitboards {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{game::Game, position::Positions::*, test_support::play};
+
+    #[test]
+    fn fifty_moves_may_be_claimed_and_seventy_five_end_the_game() {
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 98 80").unwrap();
+        play(&mut game, &[(A1, A2)]);
+        assert_eq!(game.bitboards.get_claimable_draw(&game.board), None);
+        play(&mut game, &[(E8, D8)]);
+        assert_eq!(
+            game.bitboards.get_claimable_draw(&game.board),
+            Some(DrawReason::FiftyMoveRule)
+        );
+        assert_eq!(game.get_status(), GameStatus::Ongoing);
+
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 148 80").unwrap();
+        play(&mut game, &[(A1, A2)]);
+        assert_eq!(game.get_status(), GameStatus::Ongoing);
+        play(&mut game, &[(E8, D8)]);
+        assert_eq!(
+            game.get_status(),
+            GameStatus::Draw(DrawReason::SeventyFiveMoveRule)
+        );
+
+        //pawn moves and captures start the count again
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/P7/R3K3 w - - 99 80").unwrap();
+        play(&mut game, &[(A2, A3)]);
+        assert_eq!(game.board.halfmove_clock, 0);
+        assert_eq!(game.bitboards.get_claimable_draw(&game.board), None);
+    }
+
+    #[test]
+    fn three_repetitions_may_be_claimed_and_five_end_the_game() {
+        let shuffle = [(G1, F3), (G8, F6), (F3, G1), (F6, G8)];
+        let mut game = Game::new();
+        play(&mut game, &shuffle);
+        assert_eq!(game.bitboards.get_claimable_draw(&game.board), None);
+        play(&mut game, &shuffle);
+        assert_eq!(
+            game.bitboards.get_claimable_draw(&game.board),
+            Some(DrawReason::ThreefoldRepetition)
+        );
+        assert_eq!(game.get_status(), GameStatus::Ongoing);
+        play(&mut game, &shuffle);
+        assert_eq!(game.get_status(), GameStatus::Ongoing);
+        play(&mut game, &shuffle);
+        assert_eq!(
+            game.get_status(),
+            GameStatus::Draw(DrawReason::FivefoldRepetition)
+        );
+
+        //the same placement with other castling rights is another position
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        play(&mut game, &[(E1, F1), (E8, D8), (F1, E1), (D8, E8)]);
+        play(&mut game, &[(E1, F1), (E8, D8), (F1, E1), (D8, E8)]);
+        assert_eq!(game.board.count_repetitions(), 2);
+    }
+
+    #[test]
+    fn checks_given_in_three_check_make_positions_differ() {
+        let mut game = Game::from_variant_fen(
+            "4k3/8/8/8/8/8/8/R6K w - - 3+3 0 1",
+            Variant::ThreeCheck,
+            false,
+        )
+        .unwrap();
+        let checking_shuffle = [(A1, E1), (E8, D8), (E1, A1), (D8, E8)];
+        play(&mut game, &checking_shuffle);
+        play(&mut game, &checking_shuffle);
+        //the pieces stand as they started for the third time, with two checks given since
+        assert_eq!(game.board.checks_given, [2, 0]);
+        assert_eq!(game.board.count_repetitions(), 1);
+        assert_eq!(game.bitboards.get_claimable_draw(&game.board), None);
+    }
+
+    #[test]
+    fn insufficient_material_ends_the_game() {
+        for (fen, is_draw) in [
+            ("4k3/8/8/8/8/8/8/4K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/8/1N2K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/8/2b1K3 w - - 0 1", true),
+            //bishops on one square color can never mate
+            ("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/1B6/2B1K3 w - - 0 1", true),
+            ("4k1b1/8/8/8/8/8/8/2B1K3 w - - 0 1", false),
+            ("4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1", false),
+            ("4kn2/8/8/8/8/8/8/2B1K3 w - - 0 1", false),
+            ("4k3/8/8/8/8/8/P7/4K3 w - - 0 1", false),
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", false),
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+            assert_eq!(
+                game.get_status() == GameStatus::Draw(DrawReason::InsufficientMaterial),
+                is_draw,
+                "{}",
+                fen
+            );
+        }
+
+        //capturing the last piece that could mate
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/3q4/4K3 w - - 0 1").unwrap();
+        assert_eq!(game.get_status(), GameStatus::Check);
+        play(&mut game, &[(E1, D2)]);
+        assert_eq!(
+            game.get_status(),
+            GameStatus::Draw(DrawReason::InsufficientMaterial)
+        );
+    }
+}
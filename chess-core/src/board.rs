@@ -1,5 +1,17 @@
-use crate::models::piece::{Piece, PieceColor, PieceGroup};
-use crate::models::position::Positions;
+use crate::piece::{Piece, PieceColor, PieceGroup};
+use crate::position::Positions;
+use crate::status::DrawReason;
+use crate::variant::Variant;
+use crate::zobrist::{ZOBRIST_KEYS, compute_hash};
+
+///The pieces a pocket can hold, in the order Board::pockets counts them.
+pub const POCKET_GROUPS: [PieceGroup; 5] = [
+    PieceGroup::Pawn,
+    PieceGroup::Knight,
+    PieceGroup::Bishop,
+    PieceGroup::Rook,
+    PieceGroup::Queen,
+];
 
 #[derive(Clone)]
 pub struct Board {
@@ -7,46 +19,161 @@ pub struct Board {
     pub turn_color: PieceColor,
     pub can_kingside_castle: [bool; 2],
     pub can_queenside_castle: [bool; 2],
+    pub kingside_rook_file: [u8; 2], //file of the rook each side castles with, h in standard chess
+    pub queenside_rook_file: [u8; 2], //a in standard chess
+    pub is_chess960: bool,           //castling is written as the king taking its own rook
+    pub variant: Variant,
+    pub checks_given: [u8; 2], //checks given by each color, only counted in three-check
+    pub pockets: [[u8; 5]; 2], //captured pieces each color can drop, counted in POCKET_GROUPS order, crazyhouse only
+    pub promoted_pieces: u64, //squares of pieces that were pawns, they go back to the pocket as pawns
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+    pub position_history: Vec<u64>, //position hashes before each move, oldest first
+    pub claimed_draw: Option<DrawReason>,
+    pub zobrist_hash: u64, //kept up to date by every update method below
 }
 
 impl Board {
     ///Creates and returns new Bitboards instance with all_pieces set to initial piece bitboards.
     pub fn new() -> Self {
-        Self {
+        let mut board = Self {
             squares: Board::initialize_starting_squares(),
             turn_color: PieceColor::White,
             can_kingside_castle: [true, true],
             can_queenside_castle: [true, true],
-        }
+            kingside_rook_file: [7, 7],
+            queenside_rook_file: [0, 0],
+            is_chess960: false,
+            variant: Variant::Standard,
+            checks_given: [0, 0],
+            pockets: [[0; 5]; 2],
+            promoted_pieces: 0u64,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            position_history: vec![],
+            claimed_draw: None,
+            zobrist_hash: 0u64,
+        };
+        board.zobrist_hash = compute_hash(&board, None);
+        board
     }
 
     //TODO: only allow King or Queen to be passed as "side" arg
-    pub fn update_can_castle(
-        &mut self,
-        color: PieceColor,
-        side: PieceGroup,
-        update_value: bool,
-    ) -> () {
+    pub fn update_can_castle(&mut self, color: PieceColor, side: PieceGroup, update_value: bool) {
+        let can_castle = match side {
+            PieceGroup::King => &mut self.can_kingside_castle[Piece::color_to_index(color)],
+            PieceGroup::Queen => &mut self.can_queenside_castle[Piece::color_to_index(color)],
+            _ => return,
+        };
+        if *can_castle != update_value {
+            *can_castle = update_value;
+            self.zobrist_hash ^= ZOBRIST_KEYS.castling_key(color, side);
+        }
+    }
+
+    ///Checks if the color still has the right to castle on the given side, King for kingside and Queen for queenside.
+    pub fn can_castle(&self, color: PieceColor, side: PieceGroup) -> bool {
         match side {
-            PieceGroup::King => {
-                self.can_kingside_castle[Piece::color_to_index(color)] = update_value
-            }
-            PieceGroup::Queen => {
-                self.can_queenside_castle[Piece::color_to_index(color)] = update_value
-            }
-            _ => {}
+            PieceGroup::King => self.can_kingside_castle[Piece::color_to_index(color)],
+            _ => self.can_queenside_castle[Piece::color_to_index(color)],
+        }
+    }
+
+    ///Gets the square of the rook the color castles with on the given side, King for kingside and Queen for queenside.
+    pub fn get_castling_rook_square(&self, color: PieceColor, side: PieceGroup) -> u8 {
+        let color_index = Piece::color_to_index(color);
+        let file = match side {
+            PieceGroup::King => self.kingside_rook_file[color_index],
+            _ => self.queenside_rook_file[color_index],
+        };
+        Board::get_back_rank_start(color) + file
+    }
+
+    ///Gets the a-file square of the color's first rank.
+    pub fn get_back_rank_start(color: PieceColor) -> u8 {
+        match color {
+            PieceColor::White => 0,
+            PieceColor::Black => 56,
         }
     }
 
+    ///Gets how many pieces of the group the color holds in its pocket.
+    pub fn get_pocket_count(&self, color: PieceColor, group: PieceGroup) -> u8 {
+        Board::get_pocket_index(group)
+            .map_or(0, |index| self.pockets[Piece::color_to_index(color)][index])
+    }
+
+    ///Sets how many pieces of the group the color holds in its pocket, kings are never pocketed.
+    pub fn update_pocket(&mut self, color: PieceColor, group: PieceGroup, count: u8) {
+        let Some(index) = Board::get_pocket_index(group) else {
+            return;
+        };
+        let pocket_count = &mut self.pockets[Piece::color_to_index(color)][index];
+        self.zobrist_hash ^= ZOBRIST_KEYS.pocket_key(color, index, *pocket_count)
+            ^ ZOBRIST_KEYS.pocket_key(color, index, count);
+        *pocket_count = count;
+    }
+
+    ///Sets the squares of promoted pieces, keeping the hash in step.
+    pub fn update_promoted_pieces(&mut self, promoted_pieces: u64) {
+        let mut changed_squares = self.promoted_pieces ^ promoted_pieces;
+        while changed_squares != 0 {
+            self.zobrist_hash ^= ZOBRIST_KEYS.promoted_key(changed_squares.trailing_zeros() as u8);
+            changed_squares &= changed_squares - 1;
+        }
+        self.promoted_pieces = promoted_pieces;
+    }
+
+    ///Sets how many checks the color has given, keeping the hash in step.
+    pub fn update_checks_given(&mut self, color: PieceColor, count: u8) {
+        let checks_given = &mut self.checks_given[Piece::color_to_index(color)];
+        self.zobrist_hash ^= ZOBRIST_KEYS.checks_given_key(color, *checks_given)
+            ^ ZOBRIST_KEYS.checks_given_key(color, count);
+        *checks_given = count;
+    }
+
+    fn get_pocket_index(group: PieceGroup) -> Option<usize> {
+        POCKET_GROUPS
+            .iter()
+            .position(|pocket_group| *pocket_group == group)
+    }
+
     pub fn toggle_turn_color(&mut self) -> PieceColor {
         self.turn_color = match self.turn_color {
             PieceColor::White => PieceColor::Black,
             PieceColor::Black => PieceColor::White,
         };
+        self.zobrist_hash ^= ZOBRIST_KEYS.black_to_move_key();
         self.turn_color
     }
 
-    pub fn update_square(&mut self, square: u8, update_to: Option<Piece>) -> () {
+    ///Advances the halfmove clock and fullmove number after a move by the given color.
+    pub fn update_move_counters(&mut self, color: PieceColor, resets_halfmove_clock: bool) {
+        self.halfmove_clock = match resets_halfmove_clock {
+            true => 0,
+            false => self.halfmove_clock + 1,
+        };
+        if color == PieceColor::Black {
+            self.fullmove_number += 1;
+        }
+    }
+
+    ///Counts how many times the current position has occurred, including the current occurrence.
+    pub fn count_repetitions(&self) -> usize {
+        self.position_history
+            .iter()
+            .filter(|previous_hash| **previous_hash == self.zobrist_hash)
+            .count()
+            + 1
+    }
+
+    pub fn update_square(&mut self, square: u8, update_to: Option<Piece>) {
+        for piece in [self.squares[square as usize], update_to]
+            .into_iter()
+            .flatten()
+        {
+            self.zobrist_hash ^= ZOBRIST_KEYS.piece_key(piece, square);
+        }
         self.squares[square as usize] = update_to;
     }
 
@@ -240,3 +367,9 @@ impl Board {
         squares
     }
 }
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
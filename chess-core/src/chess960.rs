@@ -0,0 +1,159 @@
+use crate::{
+    piece::{Piece, PieceColor, PieceGroup},
+    zobrist::ZobristKeys,
+};
+
+pub const CHESS960_POSITION_COUNT: u16 = 960;
+pub const STANDARD_POSITION_INDEX: u16 = 518; //RNBQKBNR
+
+///Knight placements among the five squares left after the bishops and queen, in Scharnagl order.
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+///Gets the back rank of a Chess960 starting position from its Scharnagl index, a-file first.
+pub fn get_chess960_back_rank(index: u16) -> Option<[PieceGroup; 8]> {
+    if index >= CHESS960_POSITION_COUNT {
+        return None;
+    }
+    let mut back_rank: [Option<PieceGroup>; 8] = [None; 8];
+    let mut remaining = index as usize;
+
+    //bishops on opposite colors, the light squared one on b, d, f or h
+    back_rank[remaining % 4 * 2 + 1] = Some(PieceGroup::Bishop);
+    remaining /= 4;
+    back_rank[remaining % 4 * 2] = Some(PieceGroup::Bishop);
+    remaining /= 4;
+
+    //the rest are placed counting only the squares still empty
+    let mut place_on_empty = |nth_empty: usize, group: PieceGroup| {
+        if let Some(square) = back_rank
+            .iter_mut()
+            .filter(|square| square.is_none())
+            .nth(nth_empty)
+        {
+            *square = Some(group);
+        }
+    };
+    place_on_empty(remaining % 6, PieceGroup::Queen);
+    remaining /= 6;
+    let (first_knight, second_knight) = KNIGHT_PLACEMENTS[remaining];
+    //the second knight's count skips the square the first one took
+    place_on_empty(first_knight, PieceGroup::Knight);
+    place_on_empty(second_knight - 1, PieceGroup::Knight);
+
+    //the king always ends up between the rooks
+    place_on_empty(0, PieceGroup::Rook);
+    place_on_empty(0, PieceGroup::King);
+    place_on_empty(0, PieceGroup::Rook);
+
+    Some(back_rank.map(|group| group.unwrap_or(PieceGroup::Pawn)))
+}
+
+///Generates the X-FEN of a Chess960 starting position from its Scharnagl index.
+pub fn generate_chess960_fen(index: u16) -> Option<String> {
+    let back_rank = get_chess960_back_rank(index)?;
+    let rank_fen = |color: PieceColor| -> String {
+        back_rank
+            .iter()
+            .map(|group| {
+                Piece {
+                    group: *group,
+                    color,
+                    bitboard: 0u64,
+                }
+                .to_fen_char()
+            })
+            .collect()
+    };
+
+    Some(format!(
+        "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1",
+        rank_fen(PieceColor::Black),
+        rank_fen(PieceColor::White)
+    ))
+}
+
+///Picks a starting position index from a seed, the same seed always giving the same position.
+pub fn get_chess960_index_from_seed(seed: u64) -> u16 {
+    (ZobristKeys::mix(seed) % CHESS960_POSITION_COUNT as u64) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::{generate_fen, parse_chess960_fen, parse_fen};
+
+    fn round_trip(fen: &str) -> String {
+        let (board, bitboards) = parse_chess960_fen(fen).unwrap();
+        generate_fen(&board, &bitboards)
+    }
+
+    #[test]
+    fn numbers_start_positions_like_scharnagl() {
+        assert_eq!(
+            generate_chess960_fen(STANDARD_POSITION_INDEX).unwrap(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        );
+        assert_eq!(
+            generate_chess960_fen(0).unwrap(),
+            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+        );
+        assert_eq!(
+            generate_chess960_fen(959).unwrap(),
+            "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/RKRNNQBB w KQkq - 0 1"
+        );
+        assert_eq!(generate_chess960_fen(CHESS960_POSITION_COUNT), None);
+    }
+
+    #[test]
+    fn every_start_position_is_distinct_and_valid() {
+        let mut back_ranks = std::collections::HashSet::new();
+        for index in 0..CHESS960_POSITION_COUNT {
+            let back_rank = get_chess960_back_rank(index).unwrap();
+            let files_of = |group: PieceGroup| -> Vec<usize> {
+                (0..8).filter(|file| back_rank[*file] == group).collect()
+            };
+            let (bishops, rooks, king) = (
+                files_of(PieceGroup::Bishop),
+                files_of(PieceGroup::Rook),
+                files_of(PieceGroup::King),
+            );
+            assert_ne!(bishops[0] % 2, bishops[1] % 2, "index {}", index);
+            assert!(rooks[0] < king[0] && king[0] < rooks[1], "index {}", index);
+            assert_eq!(files_of(PieceGroup::Knight).len(), 2);
+            assert_eq!(files_of(PieceGroup::Queen).len(), 1);
+            assert!(back_ranks.insert(back_rank));
+        }
+        assert!(get_chess960_index_from_seed(42) < CHESS960_POSITION_COUNT);
+        assert_eq!(
+            get_chess960_index_from_seed(42),
+            get_chess960_index_from_seed(42)
+        );
+    }
+
+    #[test]
+    fn writes_castling_rights_as_x_fen() {
+        assert_eq!(
+            round_trip("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9"),
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+        );
+        //an inner rook is named by its file so it isn't mistaken for the outer one
+        assert_eq!(
+            round_trip("rr2k2r/8/8/8/8/8/8/R3K1RR w GAb - 0 1"),
+            "rr2k2r/8/8/8/8/8/8/R3K1RR w GQb - 0 1"
+        );
+        assert!(parse_chess960_fen("rk5r/8/8/8/8/8/8/R5KR w HAha - 0 1").is_ok());
+        assert!(parse_fen("rk5r/8/8/8/8/8/8/R5KR w HAha - 0 1").is_err());
+        assert!(parse_chess960_fen("rk5r/8/8/8/8/8/8/R5KR w HHha - 0 1").is_err());
+    }
+}
@@ -0,0 +1,617 @@
+use crate::{
+    attacks,
+    bitboards::Bitboards,
+    piece::{Piece, PieceColor, PieceGroup},
+};
+use serde::{Deserialize, Serialize};
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const MAX_PHASE: i32 = 24; //phase with every minor, rook and queen still on the board
+
+///Square table laid out as the board is printed: first row is rank 8, first column is file a, from White's view.
+pub type SquareTable = [[i32; 8]; 8];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PieceValues {
+    pub pawn: i32,
+    pub knight: i32,
+    pub bishop: i32,
+    pub rook: i32,
+    pub queen: i32,
+}
+
+///Bonus per square a piece can move to, pawns and kings don't count.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MobilityWeights {
+    pub knight: i32,
+    pub bishop: i32,
+    pub rook: i32,
+    pub queen: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PieceSquareTables {
+    pub pawn: SquareTable,
+    pub knight: SquareTable,
+    pub bishop: SquareTable,
+    pub rook: SquareTable,
+    pub queen: SquareTable,
+    pub king: SquareTable,
+}
+
+///The weights for one game phase, in centipawns.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseParams {
+    pub piece_values: PieceValues,
+    pub piece_square_tables: PieceSquareTables,
+    pub mobility: MobilityWeights,
+    pub doubled_pawn: i32,     //per extra pawn on a file
+    pub isolated_pawn: i32,    //per pawn without friendly pawns on neighbouring files
+    pub passed_pawn: [i32; 8], //by rank counted from the pawn's own side
+    pub pawn_shield: i32,      //per own pawn on the two ranks in front of the king
+    pub king_zone_attack: i32, //per enemy attack on the king or the squares around it
+    pub bishop_pair: i32,
+}
+
+///Every evaluation weight, blended from middlegame to endgame as pieces come off. Loadable with serde.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvalParams {
+    pub middlegame: PhaseParams,
+    pub endgame: PhaseParams,
+}
+
+///One term of the evaluation from White's view, tapered is the blend for the position's phase.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TermScore {
+    pub middlegame: i32,
+    pub endgame: i32,
+    pub tapered: i32,
+}
+
+impl TermScore {
+    fn add(&mut self, color: PieceColor, middlegame: i32, endgame: i32) {
+        let sign = match color {
+            PieceColor::White => 1,
+            PieceColor::Black => -1,
+        };
+        self.middlegame += sign * middlegame;
+        self.endgame += sign * endgame;
+    }
+}
+
+///A position's static evaluation split into its terms, all from White's view in centipawns.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Evaluation {
+    pub phase: i32, //24 in the opening, 0 with only kings and pawns left
+    pub material: TermScore,
+    pub piece_squares: TermScore,
+    pub mobility: TermScore,
+    pub pawn_structure: TermScore,
+    pub king_safety: TermScore,
+    pub bishop_pair: TermScore,
+    pub score: i32, //tapered sum of all terms
+}
+
+impl Evaluation {
+    ///Gets the score from the view of the given side.
+    pub fn score_for(&self, color: PieceColor) -> i32 {
+        match color {
+            PieceColor::White => self.score,
+            PieceColor::Black => -self.score,
+        }
+    }
+
+    fn taper(&self, middlegame: i32, endgame: i32) -> i32 {
+        (middlegame * self.phase + endgame * (MAX_PHASE - self.phase)) / MAX_PHASE
+    }
+}
+
+///Evaluates the pieces on the bitboards, side to move plays no part.
+pub fn evaluate(bitboards: &Bitboards, params: &EvalParams) -> Evaluation {
+    let (middlegame, endgame) = (&params.middlegame, &params.endgame);
+    let mut evaluation = Evaluation::default();
+    let occupancy = bitboards
+        .all_pieces
+        .iter()
+        .fold(0u64, |all, pieces| all | pieces);
+
+    for color in [PieceColor::White, PieceColor::Black] {
+        let enemy_color = Piece::get_opposite_color(color);
+        let own_pieces = get_color_pieces(bitboards, color);
+        let own_pawns = bitboards.all_pieces[Piece::to_piece_index(color, PieceGroup::Pawn)];
+        let enemy_pawns =
+            bitboards.all_pieces[Piece::to_piece_index(enemy_color, PieceGroup::Pawn)];
+        let enemy_pawn_attacks = get_pawn_attacks(enemy_pawns, enemy_color);
+        let own_king = bitboards.all_pieces[Piece::to_piece_index(color, PieceGroup::King)];
+        let enemy_king = bitboards.all_pieces[Piece::to_piece_index(enemy_color, PieceGroup::King)];
+        let enemy_king_zone = match enemy_king {
+            0 => 0,
+            king => king | attacks::get_king_attacks(king.trailing_zeros() as u8),
+        };
+        let mut king_zone_attacks = 0i32;
+
+        for group in [
+            PieceGroup::Pawn,
+            PieceGroup::Knight,
+            PieceGroup::Bishop,
+            PieceGroup::Rook,
+            PieceGroup::Queen,
+            PieceGroup::King,
+        ] {
+            let mut pieces = bitboards.all_pieces[Piece::to_piece_index(color, group)];
+            while pieces != 0 {
+                let square = pieces.trailing_zeros() as u8;
+                pieces &= pieces - 1;
+
+                evaluation.material.add(
+                    color,
+                    get_piece_value(&middlegame.piece_values, group),
+                    get_piece_value(&endgame.piece_values, group),
+                );
+                evaluation.piece_squares.add(
+                    color,
+                    get_square_value(&middlegame.piece_square_tables, group, color, square),
+                    get_square_value(&endgame.piece_square_tables, group, color, square),
+                );
+
+                let piece_attacks = match group {
+                    PieceGroup::Pawn => attacks::get_pawn_attacks(color, square),
+                    PieceGroup::Knight => attacks::get_knight_attacks(square),
+                    PieceGroup::Bishop => attacks::get_bishop_attacks(square, occupancy),
+                    PieceGroup::Rook => attacks::get_rook_attacks(square, occupancy),
+                    PieceGroup::Queen => attacks::get_queen_attacks(square, occupancy),
+                    PieceGroup::King => 0,
+                };
+                king_zone_attacks += (piece_attacks & enemy_king_zone).count_ones() as i32;
+
+                //squares guarded by enemy pawns aren't worth much to a piece
+                let moves = (piece_attacks & !own_pieces & !enemy_pawn_attacks).count_ones() as i32;
+                evaluation.mobility.add(
+                    color,
+                    moves * get_mobility_weight(&middlegame.mobility, group),
+                    moves * get_mobility_weight(&endgame.mobility, group),
+                );
+            }
+        }
+
+        //attacks near the enemy king count against the enemy
+        evaluation.king_safety.add(
+            enemy_color,
+            king_zone_attacks * middlegame.king_zone_attack,
+            king_zone_attacks * endgame.king_zone_attack,
+        );
+        let shield_pawns = (get_shield_mask(own_king, color) & own_pawns).count_ones() as i32;
+        evaluation.king_safety.add(
+            color,
+            shield_pawns * middlegame.pawn_shield,
+            shield_pawns * endgame.pawn_shield,
+        );
+
+        add_pawn_structure(
+            &mut evaluation.pawn_structure,
+            params,
+            color,
+            own_pawns,
+            enemy_pawns,
+        );
+
+        let bishops = bitboards.all_pieces[Piece::to_piece_index(color, PieceGroup::Bishop)];
+        if bishops.count_ones() >= 2 {
+            evaluation
+                .bishop_pair
+                .add(color, middlegame.bishop_pair, endgame.bishop_pair);
+        }
+    }
+
+    evaluation.phase = get_phase(bitboards);
+    let mut total = TermScore::default();
+    for term in [
+        &mut evaluation.material,
+        &mut evaluation.piece_squares,
+        &mut evaluation.mobility,
+        &mut evaluation.pawn_structure,
+        &mut evaluation.king_safety,
+        &mut evaluation.bishop_pair,
+    ] {
+        term.tapered = (term.middlegame * evaluation.phase
+            + term.endgame * (MAX_PHASE - evaluation.phase))
+            / MAX_PHASE;
+        total.middlegame += term.middlegame;
+        total.endgame += term.endgame;
+    }
+    evaluation.score = evaluation.taper(total.middlegame, total.endgame);
+
+    evaluation
+}
+
+///Scores doubled, isolated and passed pawns for one side.
+fn add_pawn_structure(
+    term: &mut TermScore,
+    params: &EvalParams,
+    color: PieceColor,
+    own_pawns: u64,
+    enemy_pawns: u64,
+) {
+    let (middlegame, endgame) = (&params.middlegame, &params.endgame);
+
+    for file in 0..8 {
+        let file_pawns = (own_pawns & FILE_A << file).count_ones() as i32;
+        if file_pawns > 1 {
+            term.add(
+                color,
+                (file_pawns - 1) * middlegame.doubled_pawn,
+                (file_pawns - 1) * endgame.doubled_pawn,
+            );
+        }
+    }
+
+    let mut pawns = own_pawns;
+    while pawns != 0 {
+        let square = pawns.trailing_zeros() as u8;
+        pawns &= pawns - 1;
+        let (file, rank) = (square % 8, square / 8);
+
+        let neighbour_files = get_neighbour_files(file);
+        if own_pawns & neighbour_files == 0 {
+            term.add(color, middlegame.isolated_pawn, endgame.isolated_pawn);
+        }
+
+        let ranks_ahead = match color {
+            PieceColor::White => u64::MAX.checked_shl(8 * (rank as u32 + 1)).unwrap_or(0),
+            PieceColor::Black => (1u64 << (8 * rank)) - 1,
+        };
+        if enemy_pawns & (neighbour_files | FILE_A << file) & ranks_ahead == 0 {
+            let relative_rank = match color {
+                PieceColor::White => rank,
+                PieceColor::Black => 7 - rank,
+            } as usize;
+            term.add(
+                color,
+                middlegame.passed_pawn[relative_rank],
+                endgame.passed_pawn[relative_rank],
+            );
+        }
+    }
+}
+
+///Gets the squares on the king's and neighbouring files one and two ranks in front of it.
+fn get_shield_mask(king: u64, color: PieceColor) -> u64 {
+    if king == 0 {
+        return 0;
+    }
+    let square = king.trailing_zeros() as u8;
+    let (file, rank) = (square % 8, square / 8);
+    let files = get_neighbour_files(file) | FILE_A << file;
+    let mut ranks = 0u64;
+    for distance in 1..=2 {
+        let shield_rank = match color {
+            PieceColor::White => rank.checked_add(distance).filter(|rank| *rank < 8),
+            PieceColor::Black => rank.checked_sub(distance),
+        };
+        if let Some(shield_rank) = shield_rank {
+            ranks |= 0xFFu64 << (8 * shield_rank);
+        }
+    }
+    files & ranks
+}
+
+fn get_neighbour_files(file: u8) -> u64 {
+    let left = match file {
+        0 => 0,
+        _ => FILE_A << (file - 1),
+    };
+    let right = match file {
+        7 => 0,
+        _ => FILE_A << (file + 1),
+    };
+    left | right
+}
+
+fn get_pawn_attacks(pawns: u64, color: PieceColor) -> u64 {
+    let mut pawn_attacks = 0u64;
+    let mut remaining = pawns;
+    while remaining != 0 {
+        pawn_attacks |= attacks::get_pawn_attacks(color, remaining.trailing_zeros() as u8);
+        remaining &= remaining - 1;
+    }
+    pawn_attacks
+}
+
+fn get_color_pieces(bitboards: &Bitboards, color: PieceColor) -> u64 {
+    (0..bitboards.all_pieces.len())
+        .filter(|index| Piece::from_index(*index).is_some_and(|piece| piece.color == color))
+        .fold(0u64, |pieces, index| pieces | bitboards.all_pieces[index])
+}
+
+///Counts minor pieces as 1, rooks as 2 and queens as 4, capped at MAX_PHASE for extra promoted pieces.
+fn get_phase(bitboards: &Bitboards) -> i32 {
+    let phase: i32 = [PieceColor::White, PieceColor::Black]
+        .into_iter()
+        .flat_map(|color| {
+            [
+                (PieceGroup::Knight, 1),
+                (PieceGroup::Bishop, 1),
+                (PieceGroup::Rook, 2),
+                (PieceGroup::Queen, 4),
+            ]
+            .map(|(group, weight)| {
+                bitboards.all_pieces[Piece::to_piece_index(color, group)].count_ones() as i32
+                    * weight
+            })
+        })
+        .sum();
+    phase.min(MAX_PHASE)
+}
+
+fn get_piece_value(values: &PieceValues, group: PieceGroup) -> i32 {
+    match group {
+        PieceGroup::Pawn => values.pawn,
+        PieceGroup::Knight => values.knight,
+        PieceGroup::Bishop => values.bishop,
+        PieceGroup::Rook => values.rook,
+        PieceGroup::Queen => values.queen,
+        PieceGroup::King => 0,
+    }
+}
+
+fn get_mobility_weight(weights: &MobilityWeights, group: PieceGroup) -> i32 {
+    match group {
+        PieceGroup::Knight => weights.knight,
+        PieceGroup::Bishop => weights.bishop,
+        PieceGroup::Rook => weights.rook,
+        PieceGroup::Queen => weights.queen,
+        PieceGroup::Pawn | PieceGroup::King => 0,
+    }
+}
+
+fn get_square_value(
+    tables: &PieceSquareTables,
+    group: PieceGroup,
+    color: PieceColor,
+    square: u8,
+) -> i32 {
+    let table = match group {
+        PieceGroup::Pawn => &tables.pawn,
+        PieceGroup::Knight => &tables.knight,
+        PieceGroup::Bishop => &tables.bishop,
+        PieceGroup::Rook => &tables.rook,
+        PieceGroup::Queen => &tables.queen,
+        PieceGroup::King => &tables.king,
+    };
+    let (file, rank) = ((square % 8) as usize, (square / 8) as usize);
+    //tables are printed from White's side, Black reads them mirrored
+    let row = match color {
+        PieceColor::White => 7 - rank,
+        PieceColor::Black => rank,
+    };
+    table[row][file]
+}
+
+const PAWN_TABLE: SquareTable = [
+    [0, 0, 0, 0, 0, 0, 0, 0],
+    [50, 50, 50, 50, 50, 50, 50, 50],
+    [10, 10, 20, 30, 30, 20, 10, 10],
+    [5, 5, 10, 25, 25, 10, 5, 5],
+    [0, 0, 0, 20, 20, 0, 0, 0],
+    [5, -5, -10, 0, 0, -10, -5, 5],
+    [5, 10, 10, -20, -20, 10, 10, 5],
+    [0, 0, 0, 0, 0, 0, 0, 0],
+];
+
+const PAWN_ENDGAME_TABLE: SquareTable = [
+    [0, 0, 0, 0, 0, 0, 0, 0],
+    [80, 80, 80, 80, 80, 80, 80, 80],
+    [50, 50, 50, 50, 50, 50, 50, 50],
+    [30, 30, 30, 30, 30, 30, 30, 30],
+    [20, 20, 20, 20, 20, 20, 20, 20],
+    [10, 10, 10, 10, 10, 10, 10, 10],
+    [10, 10, 10, 10, 10, 10, 10, 10],
+    [0, 0, 0, 0, 0, 0, 0, 0],
+];
+
+const KNIGHT_TABLE: SquareTable = [
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+    [-40, -20, 0, 0, 0, 0, -20, -40],
+    [-30, 0, 10, 15, 15, 10, 0, -30],
+    [-30, 5, 15, 20, 20, 15, 5, -30],
+    [-30, 0, 15, 20, 20, 15, 0, -30],
+    [-30, 5, 10, 15, 15, 10, 5, -30],
+    [-40, -20, 0, 5, 5, 0, -20, -40],
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+];
+
+const BISHOP_TABLE: SquareTable = [
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+    [-10, 0, 0, 0, 0, 0, 0, -10],
+    [-10, 0, 5, 10, 10, 5, 0, -10],
+    [-10, 5, 5, 10, 10, 5, 5, -10],
+    [-10, 0, 10, 10, 10, 10, 0, -10],
+    [-10, 10, 10, 10, 10, 10, 10, -10],
+    [-10, 5, 0, 0, 0, 0, 5, -10],
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+];
+
+const ROOK_TABLE: SquareTable = [
+    [0, 0, 0, 0, 0, 0, 0, 0],
+    [5, 10, 10, 10, 10, 10, 10, 5],
+    [-5, 0, 0, 0, 0, 0, 0, -5],
+    [-5, 0, 0, 0, 0, 0, 0, -5],
+    [-5, 0, 0, 0, 0, 0, 0, -5],
+    [-5, 0, 0, 0, 0, 0, 0, -5],
+    [-5, 0, 0, 0, 0, 0, 0, -5],
+    [0, 0, 0, 5, 5, 0, 0, 0],
+];
+
+const QUEEN_TABLE: SquareTable = [
+    [-20, -10, -10, -5, -5, -10, -10, -20],
+    [-10, 0, 0, 0, 0, 0, 0, -10],
+    [-10, 0, 5, 5, 5, 5, 0, -10],
+    [-5, 0, 5, 5, 5, 5, 0, -5],
+    [0, 0, 5, 5, 5, 5, 0, -5],
+    [-10, 5, 5, 5, 5, 5, 0, -10],
+    [-10, 0, 5, 0, 0, 0, 0, -10],
+    [-20, -10, -10, -5, -5, -10, -10, -20],
+];
+
+const KING_TABLE: SquareTable = [
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-20, -30, -30, -40, -40, -30, -30, -20],
+    [-10, -20, -20, -20, -20, -20, -20, -10],
+    [20, 20, 0, 0, 0, 0, 20, 20],
+    [20, 30, 10, 0, 0, 10, 30, 20],
+];
+
+const KING_ENDGAME_TABLE: SquareTable = [
+    [-50, -40, -30, -20, -20, -30, -40, -50],
+    [-30, -20, -10, 0, 0, -10, -20, -30],
+    [-30, -10, 20, 30, 30, 20, -10, -30],
+    [-30, -10, 30, 40, 40, 30, -10, -30],
+    [-30, -10, 30, 40, 40, 30, -10, -30],
+    [-30, -10, 20, 30, 30, 20, -10, -30],
+    [-30, -30, 0, 0, 0, 0, -30, -30],
+    [-50, -30, -30, -30, -30, -30, -30, -50],
+];
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        Self {
+            middlegame: PhaseParams {
+                piece_values: PieceValues {
+                    pawn: 82,
+                    knight: 337,
+                    bishop: 365,
+                    rook: 477,
+                    queen: 1025,
+                },
+                piece_square_tables: PieceSquareTables {
+                    pawn: PAWN_TABLE,
+                    knight: KNIGHT_TABLE,
+                    bishop: BISHOP_TABLE,
+                    rook: ROOK_TABLE,
+                    queen: QUEEN_TABLE,
+                    king: KING_TABLE,
+                },
+                mobility: MobilityWeights {
+                    knight: 4,
+                    bishop: 5,
+                    rook: 2,
+                    queen: 1,
+                },
+                doubled_pawn: -10,
+                isolated_pawn: -15,
+                passed_pawn: [0, 5, 10, 15, 25, 40, 60, 0],
+                pawn_shield: 10,
+                king_zone_attack: -8,
+                bishop_pair: 30,
+            },
+            endgame: PhaseParams {
+                piece_values: PieceValues {
+                    pawn: 94,
+                    knight: 281,
+                    bishop: 297,
+                    rook: 512,
+                    queen: 936,
+                },
+                piece_square_tables: PieceSquareTables {
+                    pawn: PAWN_ENDGAME_TABLE,
+                    knight: KNIGHT_TABLE,
+                    bishop: BISHOP_TABLE,
+                    rook: ROOK_TABLE,
+                    queen: QUEEN_TABLE,
+                    king: KING_ENDGAME_TABLE,
+                },
+                mobility: MobilityWeights {
+                    knight: 4,
+                    bishop: 5,
+                    rook: 4,
+                    queen: 2,
+                },
+                doubled_pawn: -20,
+                isolated_pawn: -10,
+                passed_pawn: [0, 10, 20, 35, 60, 100, 150, 0],
+                pawn_shield: 0,
+                king_zone_attack: 0,
+                bishop_pair: 50,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::parse_fen;
+
+    fn evaluate_fen(fen: &str) -> Evaluation {
+        let (_, bitboards) = parse_fen(fen).unwrap();
+        evaluate(&bitboards, &EvalParams::default())
+    }
+
+    #[test]
+    fn starting_position_is_balanced() {
+        let evaluation = evaluate_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(evaluation.phase, MAX_PHASE);
+        assert_eq!(evaluation.score, 0);
+        assert_eq!(evaluation.material, TermScore::default());
+    }
+
+    #[test]
+    fn mirrored_positions_score_opposite() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ] {
+            //flip the ranks and swap colors, the side to move swaps with them
+            let placement = fen.split(' ').next().unwrap();
+            let mirrored_placement: Vec<String> = placement
+                .split('/')
+                .rev()
+                .map(|rank| {
+                    rank.chars()
+                        .map(|letter| match letter.is_ascii_uppercase() {
+                            true => letter.to_ascii_lowercase(),
+                            false => letter.to_ascii_uppercase(),
+                        })
+                        .collect()
+                })
+                .collect();
+            let mirrored_fen = format!("{} b - - 0 1", mirrored_placement.join("/"));
+
+            assert_eq!(evaluate_fen(fen).score, -evaluate_fen(&mirrored_fen).score);
+        }
+    }
+
+    #[test]
+    fn pawn_structure_terms() {
+        //white: doubled, isolated and passed c pawns; black: an isolated passed pawn on a3
+        let evaluation = evaluate_fen("4k3/8/8/8/2P5/p1P5/8/4K3 w - - 0 1");
+        let params = EvalParams::default();
+        let endgame = &params.endgame;
+        let white = endgame.doubled_pawn
+            + 2 * endgame.isolated_pawn
+            + endgame.passed_pawn[2]
+            + endgame.passed_pawn[3];
+        let black = endgame.isolated_pawn + endgame.passed_pawn[5];
+        assert_eq!(evaluation.pawn_structure.endgame, white - black);
+    }
+
+    #[test]
+    fn phase_tapers_toward_endgame() {
+        let evaluation = evaluate_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+        assert_eq!(evaluation.phase, 0);
+        assert_eq!(
+            evaluation.score,
+            evaluation.material.endgame
+                + evaluation.piece_squares.endgame
+                + evaluation.mobility.endgame
+                + evaluation.pawn_structure.endgame
+                + evaluation.king_safety.endgame
+                + evaluation.bishop_pair.endgame
+        );
+        assert!(evaluation.score > 0);
+    }
+}
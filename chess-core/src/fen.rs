@@ -0,0 +1,711 @@
+use crate::{
+    attacks,
+    bitboards::Bitboards,
+    board::{Board, POCKET_GROUPS},
+    piece::{Piece, PieceColor, PieceGroup},
+    position::Positions,
+    variant::Variant,
+    zobrist::compute_hash,
+};
+use std::fmt;
+
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FenError {
+    WrongFieldCount(usize),
+    WrongRankCount(usize),
+    WrongRankLength { rank: u8, length: u32 },
+    InvalidPiece(char),
+    WrongKingCount { color: PieceColor, count: u32 },
+    PawnOnBackRank(Positions),
+    InvalidTurnColor(String),
+    InvalidCastlingRights(String),
+    CastlingPiecesMissing(char),
+    InvalidEnPassant(String),
+    InvalidHalfmoveClock(String),
+    InvalidFullmoveNumber(String),
+    InvalidRemainingChecks(String),
+    InvalidPocket(String),
+    AdjacentKings,
+    OpponentInCheck(PieceColor),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::WrongFieldCount(count) => write!(
+                f,
+                "expected 6 space-separated fields (or 4 without move counters), found {}",
+                count
+            ),
+            FenError::WrongRankCount(count) => write!(
+                f,
+                "piece placement must describe 8 ranks separated by '/', found {}",
+                count
+            ),
+            FenError::WrongRankLength { rank, length } => {
+                write!(f, "rank {} describes {} squares instead of 8", rank, length)
+            }
+            FenError::InvalidPiece(letter) => {
+                write!(
+                    f,
+                    "'{}' is not a valid piece letter or empty square count",
+                    letter
+                )
+            }
+            FenError::WrongKingCount { color, count } => {
+                write!(f, "{:?} must have exactly one king, found {}", color, count)
+            }
+            FenError::PawnOnBackRank(position) => {
+                write!(f, "pawn cannot stand on {}", position.to_algebraic())
+            }
+            FenError::InvalidTurnColor(turn) => {
+                write!(f, "side to move must be 'w' or 'b', found '{}'", turn)
+            }
+            FenError::InvalidCastlingRights(rights) => write!(
+                f,
+                "castling rights must be '-' or a combination of 'KQkq' (or rook files in Chess960), found '{}'",
+                rights
+            ),
+            FenError::CastlingPiecesMissing(right) => write!(
+                f,
+                "castling right '{}' requires the king and a rook on that side of it on their starting squares",
+                right
+            ),
+            FenError::InvalidEnPassant(square) => write!(
+                f,
+                "en passant square must be '-' or a square behind a pawn that just moved two squares, found '{}'",
+                square
+            ),
+            FenError::InvalidHalfmoveClock(clock) => {
+                write!(
+                    f,
+                    "halfmove clock must be a non-negative integer, found '{}'",
+                    clock
+                )
+            }
+            FenError::InvalidFullmoveNumber(number) => {
+                write!(
+                    f,
+                    "fullmove number must be a positive integer, found '{}'",
+                    number
+                )
+            }
+            FenError::InvalidRemainingChecks(checks) => write!(
+                f,
+                "remaining checks must be written as white's and black's count from 0 to 3, e.g. '3+2', found '{}'",
+                checks
+            ),
+            FenError::InvalidPocket(pocket) => write!(
+                f,
+                "pockets must list the pieces in hand with the letters PNBRQ, uppercase for white, e.g. '[Qp]', found '{}'",
+                pocket
+            ),
+            FenError::AdjacentKings => write!(f, "kings cannot stand on adjacent squares"),
+            FenError::OpponentInCheck(color) => write!(
+                f,
+                "{:?} is in check but it is not their turn to move",
+                color
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+///Parses a FEN string into the Board and Bitboards describing its position.
+pub fn parse_fen(fen: &str) -> Result<(Board, Bitboards), FenError> {
+    parse_variant_fen(fen, Variant::Standard, false)
+}
+
+///Parses a Chess960 FEN, whose castling field is X-FEN ("KQkq" for the outermost rooks, files for inner ones) or Shredder-FEN (always files, e.g. "HFhf").
+pub fn parse_chess960_fen(fen: &str) -> Result<(Board, Bitboards), FenError> {
+    parse_variant_fen(fen, Variant::Standard, true)
+}
+
+///Parses a FEN for a game of the given variant, Three-check FENs may carry the remaining checks ("3+3") after the en passant field.
+///Crazyhouse FENs may carry the pockets after the piece placement ("...RNBQKBNR[Qp]" or as a ninth rank) and mark promoted pieces with '~'.
+pub fn parse_variant_fen(
+    fen: &str,
+    variant: Variant,
+    is_chess960: bool,
+) -> Result<(Board, Bitboards), FenError> {
+    let mut fields: Vec<&str> = fen.split_whitespace().collect();
+    let mut checks_given = [0, 0];
+    if variant.counts_checks() && fields.len() > 4 && fields[4].contains('+') {
+        checks_given = Variant::parse_remaining_checks_field(fields[4])
+            .ok_or_else(|| FenError::InvalidRemainingChecks(fields[4].to_string()))?;
+        fields.remove(4);
+    }
+    if fields.len() != 6 && fields.len() != 4 {
+        return Err(FenError::WrongFieldCount(fields.len()));
+    }
+
+    let (placement, pockets) = match variant.has_drops() {
+        true => parse_pockets(fields[0])?,
+        false => (fields[0], [[0; 5]; 2]),
+    };
+    let (squares, promoted_pieces) = parse_piece_placement(placement, variant)?;
+    let turn_color = match fields[1] {
+        "w" => PieceColor::White,
+        "b" => PieceColor::Black,
+        turn => return Err(FenError::InvalidTurnColor(turn.to_string())),
+    };
+    //variants without castling ignore the field
+    let castling_rights = match variant.allows_castling() {
+        true => fields[2],
+        false => "-",
+    };
+    let castling_rights = parse_castling_rights(castling_rights, &squares, is_chess960)?;
+    let en_passant = parse_en_passant(fields[3], &squares, turn_color)?;
+
+    let (halfmove_clock, fullmove_number) = match fields.len() {
+        6 => (
+            fields[4]
+                .parse::<u32>()
+                .map_err(|_| FenError::InvalidHalfmoveClock(fields[4].to_string()))?,
+            fields[5]
+                .parse::<u32>()
+                .ok()
+                .filter(|number| *number > 0)
+                .ok_or_else(|| FenError::InvalidFullmoveNumber(fields[5].to_string()))?,
+        ),
+        _ => (0, 1),
+    };
+
+    let mut bitboards = Bitboards::from_squares(&squares, en_passant);
+    if variant.has_royal_king() {
+        validate_kings(&mut bitboards, turn_color, variant)?;
+    }
+    let mut board = Board {
+        squares,
+        turn_color,
+        can_kingside_castle: castling_rights.can_kingside_castle,
+        can_queenside_castle: castling_rights.can_queenside_castle,
+        kingside_rook_file: castling_rights.kingside_rook_file,
+        queenside_rook_file: castling_rights.queenside_rook_file,
+        is_chess960,
+        variant,
+        checks_given,
+        pockets,
+        promoted_pieces,
+        halfmove_clock,
+        fullmove_number,
+        position_history: vec![],
+        claimed_draw: None,
+        zobrist_hash: 0u64,
+    };
+    board.zobrist_hash = compute_hash(&board, en_passant);
+
+    Ok((board, bitboards))
+}
+
+///Generates the FEN string describing the position held by board and bitboards.
+pub fn generate_fen(board: &Board, bitboards: &Bitboards) -> String {
+    format!(
+        "{} {} {}",
+        generate_position_key(board, bitboards),
+        board.halfmove_clock,
+        board.fullmove_number
+    )
+}
+
+///Generates the FEN fields that identify a position for repetition, i.e. everything but the move counters.
+pub fn generate_position_key(board: &Board, bitboards: &Bitboards) -> String {
+    let mut ranks = vec![];
+    for rank in (0..8).rev() {
+        let mut rank_fen = String::new();
+        let mut empty_squares = 0;
+
+        for file in 0..8 {
+            match board.squares[rank * 8 + file] {
+                Some(piece) => {
+                    if empty_squares > 0 {
+                        rank_fen.push_str(&empty_squares.to_string());
+                        empty_squares = 0;
+                    }
+                    rank_fen.push(piece.to_fen_char());
+                    if board.promoted_pieces & Bitboards::convert_to_bit((rank * 8 + file) as u8)
+                        != 0
+                    {
+                        rank_fen.push('~');
+                    }
+                }
+                None => empty_squares += 1,
+            }
+        }
+        if empty_squares > 0 {
+            rank_fen.push_str(&empty_squares.to_string());
+        }
+        ranks.push(rank_fen);
+    }
+
+    let turn_color = match board.turn_color {
+        PieceColor::White => "w",
+        PieceColor::Black => "b",
+    };
+
+    let mut castling_rights = String::new();
+    for color in [PieceColor::White, PieceColor::Black] {
+        for side in [PieceGroup::King, PieceGroup::Queen] {
+            if board.can_castle(color, side) {
+                castling_rights.push(get_castling_right_letter(board, color, side));
+            }
+        }
+    }
+    if castling_rights.is_empty() {
+        castling_rights.push('-');
+    }
+
+    let en_passant = bitboards
+        .get_en_passant_square()
+        .and_then(Positions::from_index)
+        .map_or("-".to_string(), |position| position.to_algebraic());
+
+    let mut placement = ranks.join("/");
+    if board.variant.has_drops() {
+        placement.push('[');
+        for color in [PieceColor::White, PieceColor::Black] {
+            for group in POCKET_GROUPS.into_iter().rev() {
+                let letter = Piece {
+                    group,
+                    color,
+                    bitboard: 0u64,
+                }
+                .to_fen_char();
+                for _ in 0..board.get_pocket_count(color, group) {
+                    placement.push(letter);
+                }
+            }
+        }
+        placement.push(']');
+    }
+
+    let mut position_key = format!(
+        "{} {} {} {}",
+        placement, turn_color, castling_rights, en_passant
+    );
+    if let Some(remaining_checks) = board.variant.get_remaining_checks_field(board) {
+        position_key.push(' ');
+        position_key.push_str(&remaining_checks);
+    }
+    position_key
+}
+
+///Splits the pockets off a crazyhouse piece placement, written in brackets or as a ninth rank, and counts them.
+fn parse_pockets(placement: &str) -> Result<(&str, [[u8; 5]; 2]), FenError> {
+    let (placement, pocket_letters) = match placement
+        .strip_suffix(']')
+        .and_then(|placement| placement.split_once('['))
+    {
+        Some(split) => split,
+        None if placement.split('/').count() == 9 => placement
+            .rsplit_once('/')
+            .expect("nine ranks have a separator"),
+        None => return Ok((placement, [[0; 5]; 2])),
+    };
+
+    let mut pockets = [[0u8; 5]; 2];
+    for letter in pocket_letters.chars().filter(|letter| *letter != '-') {
+        let piece = Piece::from_fen_char(letter)
+            .ok_or_else(|| FenError::InvalidPocket(pocket_letters.to_string()))?;
+        let pocket_index = POCKET_GROUPS
+            .iter()
+            .position(|group| *group == piece.group)
+            .ok_or_else(|| FenError::InvalidPocket(pocket_letters.to_string()))?;
+        let count = &mut pockets[Piece::color_to_index(piece.color)][pocket_index];
+        *count = count.saturating_add(1);
+    }
+
+    Ok((placement, pockets))
+}
+
+///Parses the piece placement into squares and, in crazyhouse, the squares of promoted pieces marked with '~'.
+fn parse_piece_placement(
+    placement: &str,
+    variant: Variant,
+) -> Result<(Vec<Option<Piece>>, u64), FenError> {
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != 8 {
+        return Err(FenError::WrongRankCount(ranks.len()));
+    }
+
+    let mut squares: Vec<Option<Piece>> = vec![None; 64];
+    let mut promoted_pieces = 0u64;
+    let mut king_counts = [0u32, 0u32];
+
+    //FEN lists ranks from 8 down to 1
+    for (rank_fen, rank) in ranks.iter().zip((0..8u8).rev()) {
+        let mut file = 0u32;
+
+        for letter in rank_fen.chars() {
+            if let Some(empty_squares) = letter.to_digit(10).filter(|count| (1..=8).contains(count))
+            {
+                file += empty_squares;
+                continue;
+            }
+            //'~' follows the piece it marks as promoted
+            if letter == '~' && variant.has_drops() && (1..=8).contains(&file) {
+                let square = rank * 8 + file as u8 - 1;
+                if squares[square as usize].is_none() {
+                    return Err(FenError::InvalidPiece(letter));
+                }
+                promoted_pieces |= Bitboards::convert_to_bit(square);
+                continue;
+            }
+
+            let piece = Piece::from_fen_char(letter).ok_or(FenError::InvalidPiece(letter))?;
+            if file < 8 {
+                let square = rank * 8 + file as u8;
+                if piece.group == PieceGroup::Pawn && (rank == 0 || rank == 7) {
+                    return Err(FenError::PawnOnBackRank(
+                        Positions::from_index(square).unwrap_or(Positions::A1),
+                    ));
+                }
+                if piece.group == PieceGroup::King {
+                    king_counts[Piece::color_to_index(piece.color)] += 1;
+                }
+                squares[square as usize] = Some(piece);
+            }
+            file += 1;
+        }
+
+        if file != 8 {
+            return Err(FenError::WrongRankLength {
+                rank: rank + 1,
+                length: file,
+            });
+        }
+    }
+
+    //kings that can be captured may be missing or promoted to
+    for color in [PieceColor::White, PieceColor::Black] {
+        let count = king_counts[Piece::color_to_index(color)];
+        if count != 1 && variant.has_royal_king() {
+            return Err(FenError::WrongKingCount { color, count });
+        }
+    }
+
+    Ok((squares, promoted_pieces))
+}
+
+///Rejects kings side by side and the side not to move being in check, no legal move reaches either.
+fn validate_kings(
+    bitboards: &mut Bitboards,
+    turn_color: PieceColor,
+    variant: Variant,
+) -> Result<(), FenError> {
+    let white_king =
+        bitboards.all_pieces[Piece::to_piece_index(PieceColor::White, PieceGroup::King)];
+    let black_king =
+        bitboards.all_pieces[Piece::to_piece_index(PieceColor::Black, PieceGroup::King)];
+    //atomic kings may touch, neither can capture the other
+    if !variant.has_explosions()
+        && attacks::get_king_attacks(white_king.trailing_zeros() as u8) & black_king != 0
+    {
+        return Err(FenError::AdjacentKings);
+    }
+
+    bitboards.update_attacks();
+    let waiting_color = Piece::get_opposite_color(turn_color);
+    if bitboards.is_checked(waiting_color) {
+        return Err(FenError::OpponentInCheck(waiting_color));
+    }
+    Ok(())
+}
+
+///Castling rights and the files of the rooks they castle with.
+struct CastlingRights {
+    can_kingside_castle: [bool; 2],
+    can_queenside_castle: [bool; 2],
+    kingside_rook_file: [u8; 2],
+    queenside_rook_file: [u8; 2],
+}
+
+fn parse_castling_rights(
+    castling_rights: &str,
+    squares: &[Option<Piece>],
+    is_chess960: bool,
+) -> Result<CastlingRights, FenError> {
+    let mut rights = CastlingRights {
+        can_kingside_castle: [false, false],
+        can_queenside_castle: [false, false],
+        kingside_rook_file: [7, 7],
+        queenside_rook_file: [0, 0],
+    };
+    if castling_rights == "-" {
+        return Ok(rights);
+    }
+    let invalid = || FenError::InvalidCastlingRights(castling_rights.to_string());
+
+    for right in castling_rights.chars() {
+        let color = match right.is_ascii_uppercase() {
+            true => PieceColor::White,
+            false => PieceColor::Black,
+        };
+        let back_rank_start = Board::get_back_rank_start(color);
+        let is_on_file = |file: u8, group: PieceGroup| {
+            squares[(back_rank_start + file) as usize]
+                .is_some_and(|piece| piece.color == color && piece.group == group)
+        };
+        let king_file = (0..8u8).find(|file| is_on_file(*file, PieceGroup::King));
+
+        //K and Q name the outermost rook on that side of the king, a file letter names the rook directly
+        let (side, rook_file) = match right.to_ascii_lowercase() {
+            'k' => (
+                PieceGroup::King,
+                king_file.and_then(|king_file| {
+                    (king_file + 1..8)
+                        .rev()
+                        .find(|file| is_on_file(*file, PieceGroup::Rook))
+                }),
+            ),
+            'q' => (
+                PieceGroup::Queen,
+                king_file.and_then(|king_file| {
+                    (0..king_file).find(|file| is_on_file(*file, PieceGroup::Rook))
+                }),
+            ),
+            file_letter @ 'a'..='h' if is_chess960 => {
+                let file = file_letter as u8 - b'a';
+                let side = match king_file.is_some_and(|king_file| file < king_file) {
+                    true => PieceGroup::Queen,
+                    false => PieceGroup::King,
+                };
+                let is_beside_king = king_file.is_some_and(|king_file| king_file != file);
+                (
+                    side,
+                    Some(file).filter(|file| is_beside_king && is_on_file(*file, PieceGroup::Rook)),
+                )
+            }
+            _ => return Err(invalid()),
+        };
+
+        let Some(rook_file) = rook_file else {
+            return Err(FenError::CastlingPiecesMissing(right));
+        };
+        let standard_rook_file = match side {
+            PieceGroup::King => 7,
+            _ => 0,
+        };
+        if !is_chess960 && (king_file != Some(4) || rook_file != standard_rook_file) {
+            return Err(FenError::CastlingPiecesMissing(right));
+        }
+
+        let color_index = Piece::color_to_index(color);
+        let (can_castle, castling_rook_file) = match side {
+            PieceGroup::King => (
+                &mut rights.can_kingside_castle[color_index],
+                &mut rights.kingside_rook_file[color_index],
+            ),
+            _ => (
+                &mut rights.can_queenside_castle[color_index],
+                &mut rights.queenside_rook_file[color_index],
+            ),
+        };
+        if *can_castle {
+            //right listed twice
+            return Err(invalid());
+        }
+        *can_castle = true;
+        *castling_rook_file = rook_file;
+    }
+
+    Ok(rights)
+}
+
+///Gets the X-FEN letter for a castling right, K or Q unless a rook stands between the castling rook and the board's edge.
+fn get_castling_right_letter(board: &Board, color: PieceColor, side: PieceGroup) -> char {
+    let rook_square = board.get_castling_rook_square(color, side);
+    let back_rank_start = Board::get_back_rank_start(color);
+    let mut outer_squares = match side {
+        PieceGroup::King => rook_square + 1..back_rank_start + 8,
+        _ => back_rank_start..rook_square,
+    };
+    let is_outermost = outer_squares.all(|square| {
+        board.squares[square as usize]
+            .is_none_or(|piece| piece.color != color || piece.group != PieceGroup::Rook)
+    });
+
+    let letter = match (is_outermost, side) {
+        (true, PieceGroup::King) => 'k',
+        (true, _) => 'q',
+        (false, _) => (b'a' + rook_square % 8) as char,
+    };
+    match color {
+        PieceColor::White => letter.to_ascii_uppercase(),
+        PieceColor::Black => letter,
+    }
+}
+
+fn parse_en_passant(
+    en_passant: &str,
+    squares: &[Option<Piece>],
+    turn_color: PieceColor,
+) -> Result<Option<u8>, FenError> {
+    if en_passant == "-" {
+        return Ok(None);
+    }
+
+    let invalid = || FenError::InvalidEnPassant(en_passant.to_string());
+    let square = Positions::from_algebraic(en_passant)
+        .ok_or_else(invalid)?
+        .to_index();
+
+    //en passant square sits behind the pawn that just moved two squares
+    let (expected_rank, pushed_pawn_square, pushed_pawn_color) = match turn_color {
+        PieceColor::White => (5, square.wrapping_sub(8), PieceColor::Black),
+        PieceColor::Black => (2, square + 8, PieceColor::White),
+    };
+    let is_pushed_pawn = squares
+        .get(pushed_pawn_square as usize)
+        .copied()
+        .flatten()
+        .is_some_and(|piece| piece.group == PieceGroup::Pawn && piece.color == pushed_pawn_color);
+    if square / 8 != expected_rank || !is_pushed_pawn || squares[square as usize].is_some() {
+        return Err(invalid());
+    }
+
+    Ok(Some(square))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsed_fens_generate_back_unchanged() {
+        for fen in [
+            STARTING_FEN,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 12 41",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        ] {
+            let (board, bitboards) = parse_fen(fen).unwrap();
+            assert_eq!(generate_fen(&board, &bitboards), fen);
+        }
+
+        //move counters may be left off
+        let (board, bitboards) = parse_fen("4k3/8/8/8/8/8/8/4K2R w K -").unwrap();
+        assert_eq!(
+            generate_fen(&board, &bitboards),
+            "4k3/8/8/8/8/8/8/4K2R w K - 0 1"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_fields() {
+        for (fen, error) in [
+            ("8/8/8/8/8/8/8/8 w -", FenError::WrongFieldCount(3)),
+            ("4k3/8/8/8/8/8/4K3 w - - 0 1", FenError::WrongRankCount(7)),
+            (
+                "4k3/8/8/8/8/8/8/4K4 w - - 0 1",
+                FenError::WrongRankLength { rank: 1, length: 9 },
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K2 w - - 0 1",
+                FenError::WrongRankLength { rank: 1, length: 7 },
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K2x w - - 0 1",
+                FenError::InvalidPiece('x'),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/8 w - - 0 1",
+                FenError::WrongKingCount {
+                    color: PieceColor::White,
+                    count: 0,
+                },
+            ),
+            (
+                "4k2k/8/8/8/8/8/8/4K3 w - - 0 1",
+                FenError::WrongKingCount {
+                    color: PieceColor::Black,
+                    count: 2,
+                },
+            ),
+            (
+                "P3k3/8/8/8/8/8/8/4K3 w - - 0 1",
+                FenError::PawnOnBackRank(Positions::A8),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 x - - 0 1",
+                FenError::InvalidTurnColor("x".to_string()),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/R3K2R w KX - 0 1",
+                FenError::InvalidCastlingRights("KX".to_string()),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/R3K2R w KK - 0 1",
+                FenError::InvalidCastlingRights("KK".to_string()),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+                FenError::CastlingPiecesMissing('K'),
+            ),
+            (
+                "4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1",
+                FenError::InvalidEnPassant("e3".to_string()),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w - z9 0 1",
+                FenError::InvalidEnPassant("z9".to_string()),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w - - -1 1",
+                FenError::InvalidHalfmoveClock("-1".to_string()),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w - - 0 0",
+                FenError::InvalidFullmoveNumber("0".to_string()),
+            ),
+        ] {
+            assert_eq!(parse_fen(fen).err(), Some(error), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn rejects_positions_no_legal_move_reaches() {
+        assert_eq!(
+            parse_fen("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1").err(),
+            Some(FenError::OpponentInCheck(PieceColor::Black))
+        );
+        assert_eq!(
+            parse_fen("kK6/8/8/8/8/8/8/8 w - - 0 1").err(),
+            Some(FenError::AdjacentKings)
+        );
+        assert!(parse_fen("4k3/8/8/8/8/8/8/4R1K1 b - - 0 1").is_ok());
+
+        //atomic kings may stand side by side
+        assert!(parse_variant_fen("kK6/8/8/8/8/8/8/8 w - - 0 1", Variant::Atomic, false).is_ok());
+        //kings that can be captured don't need guarding
+        assert!(
+            parse_variant_fen("kK6/8/8/8/8/8/8/8 w - - 0 1", Variant::Antichess, false).is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_variant_fields() {
+        assert_eq!(
+            parse_variant_fen(
+                "4k3/8/8/8/8/8/8/4K3 w - - 4+3 0 1",
+                Variant::ThreeCheck,
+                false
+            )
+            .err(),
+            Some(FenError::InvalidRemainingChecks("4+3".to_string()))
+        );
+        assert_eq!(
+            parse_variant_fen(
+                "4k3/8/8/8/8/8/8/4K3[Kp] w - - 0 1",
+                Variant::Crazyhouse,
+                false
+            )
+            .err(),
+            Some(FenError::InvalidPocket("Kp".to_string()))
+        );
+    }
+}
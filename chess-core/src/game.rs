@@ -0,0 +1,486 @@
+use crate::{
+    bitboards::Bitboards,
+    board::Board,
+    chess960::generate_chess960_fen,
+    fen::{FenError, STARTING_FEN, generate_fen, parse_fen, parse_variant_fen},
+    move_error::MoveError,
+    move_list::{GenerationMode, Move},
+    move_record::MoveRecord,
+    notation::{NotationMove, generate_san},
+    piece::PieceGroup,
+    status::GameStatus,
+    variant::Variant,
+};
+
+#[derive(Clone, Debug)]
+pub struct PlayedMove {
+    pub record: MoveRecord,
+    pub san: String,
+}
+
+#[derive(Clone)]
+pub struct Game {
+    pub board: Board,
+    pub bitboards: Bitboards,
+    pub move_history: Vec<PlayedMove>,
+    pub undone_moves: Vec<PlayedMove>, //most recently undone last
+    pub starting_fen: Option<String>,  //None for the standard starting position
+    pub tags: Vec<(String, String)>,   //PGN tags, overriding the seven tag roster defaults
+}
+
+impl Game {
+    ///Creates and returns new Game instance set to the starting position.
+    pub fn new() -> Self {
+        Self {
+            board: Board::new(),
+            bitboards: Bitboards::new(),
+            move_history: vec![],
+            undone_moves: vec![],
+            starting_fen: None,
+            tags: vec![],
+        }
+    }
+
+    ///Creates and returns new Game instance set to the position described by fen.
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let (board, bitboards) = parse_fen(fen)?;
+        Ok(Self {
+            starting_fen: Some(generate_fen(&board, &bitboards)),
+            board,
+            bitboards,
+            move_history: vec![],
+            undone_moves: vec![],
+            tags: vec![],
+        })
+    }
+
+    ///Creates and returns new Chess960 Game instance set to the position described by fen, castling written as the king taking its own rook.
+    pub fn from_chess960_fen(fen: &str) -> Result<Self, FenError> {
+        Game::from_variant_fen(fen, Variant::Standard, true)
+    }
+
+    ///Creates and returns new Game instance of the variant set to the position described by fen.
+    pub fn from_variant_fen(
+        fen: &str,
+        variant: Variant,
+        is_chess960: bool,
+    ) -> Result<Self, FenError> {
+        let (board, bitboards) = parse_variant_fen(fen, variant, is_chess960)?;
+        Ok(Self {
+            starting_fen: Some(generate_fen(&board, &bitboards)),
+            board,
+            bitboards,
+            move_history: vec![],
+            undone_moves: vec![],
+            tags: vec![],
+        })
+    }
+
+    ///Creates and returns new Game instance of the variant set to the starting position.
+    pub fn new_variant(variant: Variant) -> Self {
+        let (board, bitboards) = parse_variant_fen(STARTING_FEN, variant, false)
+            .expect("the starting position is valid in every variant");
+        Self {
+            board,
+            bitboards,
+            ..Game::new()
+        }
+    }
+
+    ///Creates and returns new Chess960 Game instance set to the starting position with the given Scharnagl index, 518 being standard.
+    pub fn new_chess960(index: u16) -> Option<Self> {
+        let fen = generate_chess960_fen(index)?;
+        Game::from_chess960_fen(&fen).ok()
+    }
+
+    ///Makes a move for the side to move if it is in the generated legal move set, discarding any undone moves.
+    pub fn make_move(
+        &mut self,
+        origin: u8,
+        destination: u8,
+        promotion: Option<PieceGroup>,
+    ) -> Result<PlayedMove, MoveError> {
+        if self.get_status().is_game_over() {
+            return Err(MoveError::GameOver);
+        }
+        let piece = self
+            .board
+            .squares
+            .get(origin as usize)
+            .copied()
+            .flatten()
+            .ok_or(MoveError::NoPieceOnOrigin(origin))?;
+        if piece.color != self.board.turn_color {
+            return Err(MoveError::WrongSideToMove);
+        }
+
+        let legal_move = self.find_legal_move(NotationMove {
+            origin,
+            destination,
+            promotion,
+            drop: None,
+        })?;
+        let played_move = self.apply_move(legal_move)?;
+        self.undone_moves.clear();
+        Ok(played_move)
+    }
+
+    ///Drops a piece from the side to move's pocket onto destination if that is legal, discarding any undone moves.
+    pub fn drop_piece(
+        &mut self,
+        group: PieceGroup,
+        destination: u8,
+    ) -> Result<PlayedMove, MoveError> {
+        if self.get_status().is_game_over() {
+            return Err(MoveError::GameOver);
+        }
+
+        let legal_move = self.find_legal_move(NotationMove::new_drop(group, destination))?;
+        let played_move = self.apply_move(legal_move)?;
+        self.undone_moves.clear();
+        Ok(played_move)
+    }
+
+    ///Makes a move read from a notation, a drop or a move between squares.
+    pub fn make_notation_move(
+        &mut self,
+        notation_move: NotationMove,
+    ) -> Result<PlayedMove, MoveError> {
+        match notation_move.drop {
+            Some(group) => self.drop_piece(group, notation_move.destination),
+            None => self.make_move(
+                notation_move.origin,
+                notation_move.destination,
+                notation_move.promotion,
+            ),
+        }
+    }
+
+    ///Takes back the last move, returning it if there was one.
+    pub fn undo_move(&mut self) -> Option<PlayedMove> {
+        let played_move = self.move_history.pop()?;
+        self.bitboards
+            .unmake_move(&mut self.board, &played_move.record);
+        self.board.toggle_turn_color();
+        self.board.claimed_draw = None;
+        self.undone_moves.push(played_move.clone());
+        Some(played_move)
+    }
+
+    ///Replays the last undone move, returning it if there was one.
+    pub fn redo_move(&mut self) -> Option<PlayedMove> {
+        let undone_move = &self.undone_moves.last()?.record;
+        let notation_move = NotationMove {
+            origin: undone_move.origin,
+            destination: undone_move.destination,
+            promotion: undone_move.promotion,
+            drop: undone_move.drop,
+        };
+        //the undone move stays available until it is replayed
+        let legal_move = self.find_legal_move(notation_move).ok()?;
+        let played_move = self.apply_move(legal_move).ok()?;
+        self.undone_moves.pop();
+        Some(played_move)
+    }
+
+    ///Gets the status of the game for the side to move.
+    pub fn get_status(&self) -> GameStatus {
+        let mut board = self.board.clone();
+        let mut bitboards = self.bitboards.clone();
+        let legal_moves = bitboards.get_all_legal_moves(&mut board);
+        bitboards.get_game_status(&board, &legal_moves)
+    }
+
+    ///Gets the SAN of every move played so far, oldest first.
+    pub fn get_san_history(&self) -> Vec<String> {
+        self.move_history
+            .iter()
+            .map(|played_move| played_move.san.clone())
+            .collect()
+    }
+
+    ///Finds the generated legal move with these squares, promotion and drop, or the reason there is none.
+    fn find_legal_move(&mut self, notation_move: NotationMove) -> Result<Move, MoveError> {
+        let NotationMove {
+            origin,
+            destination,
+            promotion,
+            drop,
+        } = notation_move;
+        let legal_moves = self
+            .bitboards
+            .generate_legal_moves(&self.board, GenerationMode::All);
+
+        if let Some(group) = drop {
+            if legal_moves.contains(&Move::new_drop(group, destination)) {
+                return Ok(Move::new_drop(group, destination));
+            }
+            //replay the drop to learn why it is illegal
+            return Err(self
+                .bitboards
+                .clone()
+                .drop_piece(&mut self.board.clone(), group, destination)
+                .err()
+                .unwrap_or(MoveError::IllegalDestination(destination)));
+        }
+
+        let mut square_matches = legal_moves
+            .iter()
+            .filter(|legal_move| {
+                legal_move.origin() == origin
+                    && legal_move.destination() == destination
+                    && legal_move.drop().is_none()
+            })
+            .peekable();
+
+        if square_matches.peek().is_some() {
+            let mut is_promotion = false;
+            for legal_move in square_matches {
+                if legal_move.promotion() == promotion {
+                    return Ok(*legal_move);
+                }
+                is_promotion |= legal_move.promotion().is_some();
+            }
+            return Err(match promotion {
+                None => MoveError::PromotionRequired,
+                Some(group) if is_promotion => MoveError::InvalidPromotionPiece(group),
+                Some(_) => MoveError::PromotionNotAllowed,
+            });
+        }
+
+        //the piece can reach the square but the move is illegal, replay it to learn why
+        let pseudo_legal_moves = self
+            .bitboards
+            .clone()
+            .generate_pseudo_legal_moves(&self.board, GenerationMode::All);
+        match pseudo_legal_moves.iter().find(|pseudo_legal_move| {
+            pseudo_legal_move.origin() == origin && pseudo_legal_move.destination() == destination
+        }) {
+            Some(pseudo_legal_move) => Err(self
+                .bitboards
+                .clone()
+                .make_move(&mut self.board.clone(), *pseudo_legal_move)
+                .err()
+                .unwrap_or(MoveError::KingLeftInCheck)),
+            None => Err(MoveError::IllegalDestination(destination)),
+        }
+    }
+
+    fn apply_move(&mut self, legal_move: Move) -> Result<PlayedMove, MoveError> {
+        let san = generate_san(&self.board, &self.bitboards, NotationMove::from(legal_move))
+            .expect("generated moves are legal");
+        let record = self.bitboards.make_move(&mut self.board, legal_move)?;
+        self.board.toggle_turn_color();
+
+        let played_move = PlayedMove { record, san };
+        self.move_history.push(played_move.clone());
+        Ok(played_move)
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        piece::PieceColor,
+        position::Positions::{self, *},
+    };
+
+    fn square(position: Positions) -> u8 {
+        position.to_index()
+    }
+
+    #[test]
+    fn rejects_moves_outside_the_legal_set() {
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert_eq!(
+            game.make_move(square(A1), square(B2), None).unwrap_err(),
+            MoveError::IllegalDestination(square(B2))
+        );
+        assert_eq!(
+            game.make_move(square(E8), square(E7), None).unwrap_err(),
+            MoveError::WrongSideToMove
+        );
+
+        let mut game = Game::new();
+        assert_eq!(
+            game.make_move(square(E2), square(E5), None).unwrap_err(),
+            MoveError::IllegalDestination(square(E5))
+        );
+        assert_eq!(
+            game.make_move(square(B1), square(B3), None).unwrap_err(),
+            MoveError::IllegalDestination(square(B3))
+        );
+        assert!(game.move_history.is_empty());
+        assert_eq!(game.board.turn_color, PieceColor::White);
+
+        //taking en passant would uncover the bishop's check on the a2 king
+        let mut game = Game::from_fen("6b1/8/8/3pP3/8/8/K7/7k w - d6 0 1").unwrap();
+        assert_eq!(
+            game.make_move(square(E5), square(D6), None).unwrap_err(),
+            MoveError::PinnedPiece
+        );
+        assert!(game.move_history.is_empty());
+    }
+
+    #[test]
+    fn finished_games_reject_further_moves() {
+        //fool's mate
+        let mut game = Game::new();
+        for (origin, destination) in [(F2, F3), (E7, E5), (G2, G4), (D8, H4)] {
+            game.make_move(square(origin), square(destination), None)
+                .unwrap();
+        }
+        assert_eq!(game.get_status(), GameStatus::Checkmate);
+        assert_eq!(
+            game.make_move(square(E2), square(E4), None).unwrap_err(),
+            MoveError::GameOver
+        );
+        assert_eq!(game.move_history.len(), 4);
+
+        let mut game = Game::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(game.get_status(), GameStatus::Stalemate);
+        assert_eq!(
+            game.make_move(square(H8), square(G8), None).unwrap_err(),
+            MoveError::GameOver
+        );
+        assert!(game.move_history.is_empty());
+    }
+
+    #[test]
+    fn explains_illegal_moves_the_piece_could_make() {
+        //the e-file knight is pinned, the f-file one is free but the king is in check from the a5 bishop
+        let mut game = Game::from_fen("4r2k/8/8/b7/8/8/4NN2/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            game.make_move(square(F2), square(H3), None).unwrap_err(),
+            MoveError::KingLeftInCheck
+        );
+        let mut game = Game::from_fen("4r2k/8/8/8/8/8/4NN2/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            game.make_move(square(E2), square(C3), None).unwrap_err(),
+            MoveError::PinnedPiece
+        );
+
+        let mut game = Game::from_fen("7k/P7/8/8/8/8/8/K5R1 w - - 0 1").unwrap();
+        assert_eq!(
+            game.make_move(square(A7), square(A8), None).unwrap_err(),
+            MoveError::PromotionRequired
+        );
+        assert_eq!(
+            game.make_move(square(A7), square(A8), Some(PieceGroup::King))
+                .unwrap_err(),
+            MoveError::InvalidPromotionPiece(PieceGroup::King)
+        );
+        assert_eq!(
+            game.make_move(square(G1), square(G7), Some(PieceGroup::Queen))
+                .unwrap_err(),
+            MoveError::PromotionNotAllowed
+        );
+        assert!(
+            game.make_move(square(A7), square(A8), Some(PieceGroup::Knight))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn accepted_moves_match_highlighted_moves() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+            let mut board = game.board.clone();
+            let highlighted_moves = game.bitboards.clone().get_all_legal_moves(&mut board);
+
+            for origin in 0..64u8 {
+                for destination in 0..64u8 {
+                    let is_highlighted =
+                        highlighted_moves[origin as usize] & (1u64 << destination) != 0;
+                    let is_accepted =
+                        [None, Some(PieceGroup::Queen)]
+                            .into_iter()
+                            .any(|promotion| {
+                                game.clone()
+                                    .make_move(origin, destination, promotion)
+                                    .is_ok()
+                            });
+                    assert_eq!(
+                        is_highlighted, is_accepted,
+                        "{} {}->{}",
+                        fen, origin, destination
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn redo_replays_undone_moves() {
+        let mut game = Game::new();
+        game.make_move(square(E2), square(E4), None).unwrap();
+        game.make_move(square(E7), square(E5), None).unwrap();
+        game.undo_move();
+        game.undo_move();
+        assert_eq!(game.redo_move().unwrap().san, "e4");
+        assert_eq!(game.redo_move().unwrap().san, "e5");
+        assert!(game.redo_move().is_none());
+        assert_eq!(game.get_san_history(), vec!["e4", "e5"]);
+    }
+
+    #[test]
+    fn failed_redo_keeps_the_undone_move() {
+        let mut game = Game::new();
+        game.make_move(square(E2), square(E4), None).unwrap();
+        game.undo_move();
+        let starting_position = (game.board.clone(), game.bitboards.clone());
+
+        //e4 can't be replayed once the position no longer allows it
+        let bare_kings = Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        game.board = bare_kings.board;
+        game.bitboards = bare_kings.bitboards;
+        assert!(game.redo_move().is_none());
+        assert_eq!(game.undone_moves.len(), 1);
+
+        (game.board, game.bitboards) = starting_position;
+        assert_eq!(game.redo_move().unwrap().san, "e4");
+        assert!(game.undone_moves.is_empty());
+    }
+
+    #[test]
+    fn chess960_castles_by_taking_the_own_rook() {
+        let fen = "rk5r/8/8/8/8/8/8/R5KR w HAha - 0 1";
+        let mut game = Game::from_chess960_fen(fen).unwrap();
+
+        //the king stays on g1 and the rook hops over it to f1
+        let played_move = game.make_move(square(G1), square(H1), None).unwrap();
+        assert_eq!(played_move.san, "O-O");
+        game.make_move(square(B8), square(A8), None).unwrap();
+        assert_eq!(
+            generate_fen(&game.board, &game.bitboards),
+            "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2"
+        );
+        assert_eq!(game.get_san_history(), ["O-O", "O-O-O"]);
+
+        game.undo_move();
+        game.undo_move();
+        assert_eq!(
+            generate_fen(&game.board, &game.bitboards),
+            "rk5r/8/8/8/8/8/8/R5KR w KQkq - 0 1"
+        );
+        //a standard game doesn't treat moving onto the own rook as castling
+        assert!(Game::new_chess960(518).unwrap().board.is_chess960);
+        assert_eq!(
+            Game::new()
+                .make_move(square(E1), square(H1), None)
+                .unwrap_err(),
+            MoveError::IllegalDestination(square(H1))
+        );
+    }
+}
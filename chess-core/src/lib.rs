@@ -0,0 +1,44 @@
+//! Chess rules without a web stack: board representation, legal move generation, notation, FEN/PGN, Chess960 and other variants, perft, evaluation and search.
+//!
+//! ```
+//! use chess_core::{GenerationMode, fen::parse_fen, perft::perft};
+//!
+//! let (board, mut bitboards) = parse_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+//! let moves = bitboards.generate_legal_moves(&board, GenerationMode::All);
+//! assert_eq!(moves.len(), 20);
+//! assert_eq!(perft(&board, &bitboards, 2), 400);
+//! ```
+
+pub mod attacks;
+pub mod bitboards;
+pub mod board;
+pub mod chess960;
+pub mod evaluation;
+pub mod fen;
+pub mod game;
+pub mod move_error;
+pub mod move_list;
+pub mod move_record;
+pub mod notation;
+pub mod perft;
+pub mod pgn;
+pub mod piece;
+pub mod position;
+pub mod search;
+pub mod status;
+#[cfg(test)]
+mod test_support;
+pub mod variant;
+pub mod zobrist;
+
+pub use bitboards::Bitboards;
+pub use board::Board;
+pub use evaluation::{EvalParams, Evaluation};
+pub use game::{Game, PlayedMove};
+pub use move_error::MoveError;
+pub use move_list::{GenerationMode, Move, MoveFlag, MoveList};
+pub use piece::{Piece, PieceColor, PieceGroup};
+pub use position::Positions;
+pub use search::{SearchLimits, SearchResult, Searcher};
+pub use status::{DrawReason, GameStatus, VariantWin, WinReason};
+pub use variant::Variant;
@@ -0,0 +1,78 @@
+use crate::{piece::PieceGroup, position::Positions};
+use std::fmt;
+
+///Why a move was rejected by the rules engine.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    NoPieceOnOrigin(u8),
+    WrongSideToMove,
+    IllegalDestination(u8),
+    PinnedPiece,
+    KingLeftInCheck,
+    PromotionRequired,
+    PromotionNotAllowed,
+    InvalidPromotionPiece(PieceGroup),
+    PieceNotInPocket(PieceGroup),
+    GameOver,
+}
+
+impl MoveError {
+    ///Gets the stable, machine-readable code clients can match on.
+    pub fn code(self) -> &'static str {
+        match self {
+            MoveError::NoPieceOnOrigin(_) => "no_piece_on_origin",
+            MoveError::WrongSideToMove => "wrong_side_to_move",
+            MoveError::IllegalDestination(_) => "illegal_destination",
+            MoveError::PinnedPiece => "pinned_piece",
+            MoveError::KingLeftInCheck => "king_left_in_check",
+            MoveError::PromotionRequired => "promotion_required",
+            MoveError::PromotionNotAllowed => "promotion_not_allowed",
+            MoveError::InvalidPromotionPiece(_) => "invalid_promotion_piece",
+            MoveError::PieceNotInPocket(_) => "piece_not_in_pocket",
+            MoveError::GameOver => "game_over",
+        }
+    }
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let square_name = |square: &u8| {
+            Positions::from_index(*square)
+                .map_or(square.to_string(), |position| position.to_algebraic())
+        };
+        match self {
+            MoveError::NoPieceOnOrigin(square) => {
+                write!(f, "there is no piece on {}", square_name(square))
+            }
+            MoveError::WrongSideToMove => write!(f, "it is the other side's turn to move"),
+            MoveError::IllegalDestination(square) => {
+                write!(f, "the piece cannot move to {}", square_name(square))
+            }
+            MoveError::PinnedPiece => {
+                write!(
+                    f,
+                    "the piece is pinned and cannot leave the line to its king"
+                )
+            }
+            MoveError::KingLeftInCheck => write!(f, "the move would leave the king in check"),
+            MoveError::PromotionRequired => write!(
+                f,
+                "a pawn reaching the last rank must promote to a knight, bishop, rook or queen"
+            ),
+            MoveError::PromotionNotAllowed => {
+                write!(f, "only a pawn reaching the last rank can promote")
+            }
+            MoveError::InvalidPromotionPiece(group) => {
+                let piece_name = format!("{:?}", group).to_lowercase();
+                write!(f, "a pawn cannot promote to a {}", piece_name)
+            }
+            MoveError::PieceNotInPocket(group) => {
+                let piece_name = format!("{:?}", group).to_lowercase();
+                write!(f, "there is no {} in the pocket to drop", piece_name)
+            }
+            MoveError::GameOver => write!(f, "the game is over, no more moves can be made"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
@@ -0,0 +1,414 @@
+use crate::{
+    piece::{Piece, PieceColor, PieceGroup},
+    position::Positions,
+};
+use serde::Deserialize;
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+const ORIGIN_MASK: u16 = 0x003F;
+const DESTINATION_SHIFT: u16 = 6;
+const FLAG_SHIFT: u16 = 12;
+const MAX_MOVES: usize = 512; //218 at most in standard chess, crazyhouse drops add a few hundred more
+
+///What kind of move a Move is, promotions carry the piece promoted to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MoveFlag {
+    Quiet,
+    DoublePawnPush,
+    KingsideCastle,
+    QueensideCastle,
+    Capture,
+    EnPassant,
+    Promotion(PieceGroup),
+    PromotionCapture(PieceGroup),
+    Drop(PieceGroup), //a piece put down from the pocket in crazyhouse
+}
+
+impl MoveFlag {
+    fn to_bits(self) -> u16 {
+        match self {
+            MoveFlag::Quiet => 0,
+            MoveFlag::DoublePawnPush => 1,
+            MoveFlag::KingsideCastle => 2,
+            MoveFlag::QueensideCastle => 3,
+            MoveFlag::Capture => 4,
+            MoveFlag::EnPassant => 5,
+            //promoting to a king is only legal in antichess, it takes the two unused values
+            MoveFlag::Promotion(PieceGroup::King) => 6,
+            MoveFlag::PromotionCapture(PieceGroup::King) => 7,
+            MoveFlag::Promotion(group) => 8 | MoveFlag::promotion_to_bits(group),
+            MoveFlag::PromotionCapture(group) => 12 | MoveFlag::promotion_to_bits(group),
+            MoveFlag::Drop(group) => MoveFlag::drop_to_bits(group),
+        }
+    }
+
+    fn from_bits(bits: u16) -> Self {
+        match bits {
+            0 => MoveFlag::Quiet,
+            1 => MoveFlag::DoublePawnPush,
+            2 => MoveFlag::KingsideCastle,
+            3 => MoveFlag::QueensideCastle,
+            4 => MoveFlag::Capture,
+            5 => MoveFlag::EnPassant,
+            6 => MoveFlag::Promotion(PieceGroup::King),
+            7 => MoveFlag::PromotionCapture(PieceGroup::King),
+            8..=11 => MoveFlag::Promotion(MoveFlag::promotion_from_bits(bits)),
+            12..=15 => MoveFlag::PromotionCapture(MoveFlag::promotion_from_bits(bits)),
+            _ => unreachable!("move flags are four bits, {} is out of range", bits),
+        }
+    }
+
+    fn promotion_to_bits(group: PieceGroup) -> u16 {
+        match group {
+            PieceGroup::Knight => 0,
+            PieceGroup::Bishop => 1,
+            PieceGroup::Rook => 2,
+            _ => 3,
+        }
+    }
+
+    fn promotion_from_bits(bits: u16) -> PieceGroup {
+        match bits & 0b11 {
+            0 => PieceGroup::Knight,
+            1 => PieceGroup::Bishop,
+            2 => PieceGroup::Rook,
+            _ => PieceGroup::Queen,
+        }
+    }
+
+    fn drop_to_bits(group: PieceGroup) -> u16 {
+        match group {
+            PieceGroup::Pawn => 0,
+            PieceGroup::Knight => 1,
+            PieceGroup::Bishop => 2,
+            PieceGroup::Rook => 3,
+            _ => 4,
+        }
+    }
+
+    fn drop_from_bits(bits: u16) -> PieceGroup {
+        match bits {
+            0 => PieceGroup::Pawn,
+            1 => PieceGroup::Knight,
+            2 => PieceGroup::Bishop,
+            3 => PieceGroup::Rook,
+            _ => PieceGroup::Queen,
+        }
+    }
+}
+
+///A move packed into 16 bits: origin in bits 0-5, destination in bits 6-11 and its MoveFlag in bits 12-15.
+///Drops have the destination as their origin, no piece moves onto its own square, and the flag bits hold the dropped piece.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Move(u16);
+
+impl Move {
+    pub fn new(origin: u8, destination: u8, flag: MoveFlag) -> Self {
+        Self(
+            origin as u16 & ORIGIN_MASK
+                | (destination as u16 & ORIGIN_MASK) << DESTINATION_SHIFT
+                | flag.to_bits() << FLAG_SHIFT,
+        )
+    }
+
+    ///Creates a drop of a pocket piece onto destination.
+    pub fn new_drop(group: PieceGroup, destination: u8) -> Self {
+        Move::new(destination, destination, MoveFlag::Drop(group))
+    }
+
+    pub fn origin(self) -> u8 {
+        (self.0 & ORIGIN_MASK) as u8
+    }
+
+    pub fn destination(self) -> u8 {
+        (self.0 >> DESTINATION_SHIFT & ORIGIN_MASK) as u8
+    }
+
+    pub fn flag(self) -> MoveFlag {
+        match self.origin() == self.destination() {
+            true => MoveFlag::Drop(MoveFlag::drop_from_bits(self.0 >> FLAG_SHIFT)),
+            false => MoveFlag::from_bits(self.0 >> FLAG_SHIFT),
+        }
+    }
+
+    pub fn promotion(self) -> Option<PieceGroup> {
+        match self.flag() {
+            MoveFlag::Promotion(group) | MoveFlag::PromotionCapture(group) => Some(group),
+            _ => None,
+        }
+    }
+
+    ///Gets the piece put down from the pocket if the move is a drop.
+    pub fn drop(self) -> Option<PieceGroup> {
+        match self.flag() {
+            MoveFlag::Drop(group) => Some(group),
+            _ => None,
+        }
+    }
+
+    ///Checks if the move takes a piece, including en passant.
+    pub fn is_capture(self) -> bool {
+        matches!(
+            self.flag(),
+            MoveFlag::Capture | MoveFlag::EnPassant | MoveFlag::PromotionCapture(_)
+        )
+    }
+
+    pub fn is_castle(self) -> bool {
+        matches!(
+            self.flag(),
+            MoveFlag::KingsideCastle | MoveFlag::QueensideCastle
+        )
+    }
+
+    ///Gets the move in UCI notation, e.g. "e2e4", "e7e8q" or the drop "N@f3".
+    pub fn to_uci(self) -> String {
+        if let Some(group) = self.drop() {
+            let destination = Positions::from_index(self.destination())
+                .map_or(String::new(), |position| position.to_algebraic());
+            let piece = Piece {
+                group,
+                color: PieceColor::White,
+                bitboard: 0u64,
+            };
+            return format!("{}@{}", piece.to_fen_char(), destination);
+        }
+        let mut uci: String = [self.origin(), self.destination()]
+            .iter()
+            .filter_map(|square| Positions::from_index(*square))
+            .map(|position| position.to_algebraic())
+            .collect();
+        if let Some(promotion) = self.promotion() {
+            uci.push(match promotion {
+                PieceGroup::Knight => 'n',
+                PieceGroup::Bishop => 'b',
+                PieceGroup::Rook => 'r',
+                PieceGroup::King => 'k',
+                _ => 'q',
+            });
+        }
+        uci
+    }
+}
+
+impl fmt::Debug for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Move({} {:?})", self.to_uci(), self.flag())
+    }
+}
+
+///Which moves a generator call produces.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationMode {
+    All,
+    Captures, //includes en passant and capturing promotions
+    Quiets,   //everything else, including castling, non-capturing promotions and drops
+}
+
+///A fixed-capacity list of generated moves that lives on the stack.
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    pub fn new() -> Self {
+        Self {
+            moves: [Move::default(); MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, generated_move: Move) {
+        self.moves[self.len] = generated_move;
+        self.len += 1;
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::parse_fen;
+
+    fn generate(fen: &str, mode: GenerationMode, is_legal: bool) -> MoveList {
+        let (board, mut bitboards) = parse_fen(fen).unwrap();
+        match is_legal {
+            true => bitboards.generate_legal_moves(&board, mode),
+            false => bitboards.generate_pseudo_legal_moves(&board, mode),
+        }
+    }
+
+    fn count_flag(move_list: &MoveList, is_flag: impl Fn(MoveFlag) -> bool) -> usize {
+        move_list
+            .iter()
+            .filter(|generated_move| is_flag(generated_move.flag()))
+            .count()
+    }
+
+    #[test]
+    fn move_packs_and_unpacks() {
+        let flags = [
+            MoveFlag::Quiet,
+            MoveFlag::DoublePawnPush,
+            MoveFlag::KingsideCastle,
+            MoveFlag::QueensideCastle,
+            MoveFlag::Capture,
+            MoveFlag::EnPassant,
+        ]
+        .into_iter()
+        .chain(
+            [
+                PieceGroup::Knight,
+                PieceGroup::Bishop,
+                PieceGroup::Rook,
+                PieceGroup::Queen,
+                PieceGroup::King,
+            ]
+            .into_iter()
+            .flat_map(|group| {
+                [
+                    MoveFlag::Promotion(group),
+                    MoveFlag::PromotionCapture(group),
+                ]
+            }),
+        );
+        for flag in flags {
+            let packed = Move::new(63, 0, flag);
+            assert_eq!((packed.origin(), packed.destination()), (63, 0));
+            assert_eq!(packed.flag(), flag);
+        }
+        assert_eq!(
+            Move::new(52, 60, MoveFlag::Promotion(PieceGroup::Knight)).to_uci(),
+            "e7e8n"
+        );
+
+        for group in [
+            PieceGroup::Pawn,
+            PieceGroup::Knight,
+            PieceGroup::Bishop,
+            PieceGroup::Rook,
+            PieceGroup::Queen,
+        ] {
+            let packed = Move::new_drop(group, 21);
+            assert_eq!(packed.destination(), 21);
+            assert_eq!(packed.drop(), Some(group));
+            assert!(!packed.is_capture());
+        }
+        assert_eq!(Move::new_drop(PieceGroup::Knight, 21).to_uci(), "N@f3");
+    }
+
+    #[test]
+    fn captures_and_quiets_split_all_moves() {
+        let kiwipete = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let all_moves = generate(kiwipete, GenerationMode::All, true);
+        let captures = generate(kiwipete, GenerationMode::Captures, true);
+        let quiets = generate(kiwipete, GenerationMode::Quiets, true);
+
+        assert_eq!(all_moves.len(), 48);
+        assert_eq!(captures.len(), 8);
+        assert_eq!(captures.len() + quiets.len(), all_moves.len());
+        assert!(captures.iter().all(|capture| capture.is_capture()));
+        assert!(quiets.iter().all(|quiet| !quiet.is_capture()));
+        assert_eq!(
+            count_flag(&quiets, |flag| flag == MoveFlag::KingsideCastle),
+            1
+        );
+        assert_eq!(
+            count_flag(&quiets, |flag| flag == MoveFlag::QueensideCastle),
+            1
+        );
+    }
+
+    #[test]
+    fn special_moves_are_flagged() {
+        let en_passant = generate(
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            GenerationMode::Captures,
+            true,
+        );
+        assert_eq!(
+            count_flag(&en_passant, |flag| flag == MoveFlag::EnPassant),
+            1
+        );
+
+        let double_pushes = generate(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            GenerationMode::All,
+            true,
+        );
+        assert_eq!(
+            count_flag(&double_pushes, |flag| flag == MoveFlag::DoublePawnPush),
+            8
+        );
+
+        //d7 can only promote by taking the bishop on c8, the queen on d8 blocks the push
+        let promotions = generate(
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            GenerationMode::Captures,
+            true,
+        );
+        assert_eq!(
+            count_flag(&promotions, |flag| matches!(
+                flag,
+                MoveFlag::PromotionCapture(_)
+            )),
+            4
+        );
+    }
+
+    #[test]
+    fn pseudo_legal_moves_ignore_pins_and_checks() {
+        //knight on e2 is pinned by the rook on e3
+        let fen = "4k3/8/8/8/8/4r3/4N3/4K3 w - - 0 1";
+        let legal = generate(fen, GenerationMode::All, true);
+        let pseudo_legal = generate(fen, GenerationMode::All, false);
+
+        assert_eq!(legal.len(), 4);
+        assert_eq!(pseudo_legal.len(), 10);
+        assert!(
+            legal
+                .iter()
+                .all(|legal_move| pseudo_legal.contains(legal_move))
+        );
+    }
+}
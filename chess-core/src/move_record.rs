@@ -0,0 +1,24 @@
+use crate::piece::{Piece, PieceGroup};
+use serde::{Deserialize, Serialize};
+
+///Everything needed to unmake a move and restore the position before it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveRecord {
+    pub piece: Piece,
+    pub origin: u8,
+    pub destination: u8,
+    pub captured_piece: Option<Piece>,
+    pub captured_square: u8, //differs from destination only for en passant
+    pub promotion: Option<PieceGroup>,
+    pub drop: Option<PieceGroup>, //piece put down from the pocket, origin then equals destination
+    pub castling_rook: Option<(u8, u8)>, //rook origin and destination
+    pub exploded_pieces: [Option<(u8, Piece)>; 8], //pieces around an atomic capture blown off their squares
+    pub prior_can_kingside_castle: [bool; 2],
+    pub prior_can_queenside_castle: [bool; 2],
+    pub prior_en_passant: Option<u8>,
+    pub prior_halfmove_clock: u32,
+    pub prior_fullmove_number: u32,
+    pub prior_checks_given: [u8; 2],
+    pub prior_pockets: [[u8; 5]; 2],
+    pub prior_promoted_pieces: u64,
+}
@@ -0,0 +1,611 @@
+use crate::{
+    bitboards::Bitboards,
+    board::{Board, POCKET_GROUPS},
+    move_list::Move,
+    piece::{Piece, PieceColor, PieceGroup},
+    position::Positions,
+    status::GameStatus,
+};
+use std::fmt;
+
+///A move described by its squares, as read from or written to a notation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NotationMove {
+    pub origin: u8,
+    pub destination: u8,
+    pub promotion: Option<PieceGroup>,
+    pub drop: Option<PieceGroup>, //piece put down from the pocket, origin then equals destination
+}
+
+impl NotationMove {
+    ///Creates a drop of a pocket piece onto destination.
+    pub fn new_drop(group: PieceGroup, destination: u8) -> Self {
+        Self {
+            origin: destination,
+            destination,
+            promotion: None,
+            drop: Some(group),
+        }
+    }
+}
+
+impl From<Move> for NotationMove {
+    fn from(chess_move: Move) -> Self {
+        Self {
+            origin: chess_move.origin(),
+            destination: chess_move.destination(),
+            promotion: chess_move.promotion(),
+            drop: chess_move.drop(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum NotationError {
+    InvalidSyntax(String),
+    IllegalMove(String),
+    AmbiguousMove(String),
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::InvalidSyntax(notation) => {
+                write!(f, "'{}' is not valid move notation", notation)
+            }
+            NotationError::IllegalMove(notation) => {
+                write!(f, "'{}' is not a legal move in this position", notation)
+            }
+            NotationError::AmbiguousMove(notation) => write!(
+                f,
+                "'{}' matches more than one legal move, add the origin file or rank",
+                notation
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+///Parses a move in Standard Algebraic Notation, e.g. "Nbd2", "exd6 e.p.", "O-O-O", "e8=Q+" or the drop "N@f3", for the side to move.
+pub fn parse_san(
+    board: &Board,
+    bitboards: &Bitboards,
+    san: &str,
+) -> Result<NotationMove, NotationError> {
+    let invalid_syntax = || NotationError::InvalidSyntax(san.to_string());
+    let illegal_move = || NotationError::IllegalMove(san.to_string());
+
+    let notation = san
+        .trim()
+        .trim_end_matches("e.p.")
+        .trim_end()
+        .trim_end_matches(['+', '#', '!', '?']);
+    let legal_moves = get_legal_moves(board, bitboards);
+
+    //castling
+    let castle_side = match notation {
+        "O-O" | "0-0" => Some(PieceGroup::King),
+        "O-O-O" | "0-0-0" => Some(PieceGroup::Queen),
+        _ => None,
+    };
+    if let Some(side) = castle_side {
+        let king_bitboard =
+            bitboards.all_pieces[Piece::to_piece_index(board.turn_color, PieceGroup::King)];
+        let origin = king_bitboard.trailing_zeros() as u8;
+        let destination = Bitboards::get_castling_destination(board, board.turn_color, side);
+        if !board.can_castle(board.turn_color, side)
+            || legal_moves[origin as usize] & Bitboards::convert_to_bit(destination) == 0
+        {
+            return Err(illegal_move());
+        }
+        return Ok(NotationMove {
+            origin,
+            destination,
+            promotion: None,
+            drop: None,
+        });
+    }
+
+    //drops, pawn drops may leave out the letter
+    if let Some((letter, square)) = notation.split_once('@') {
+        let group = match letter {
+            "" | "P" => PieceGroup::Pawn,
+            _ => letter
+                .chars()
+                .next()
+                .filter(|_| letter.len() == 1)
+                .and_then(piece_group_from_letter)
+                .filter(|group| *group != PieceGroup::King)
+                .ok_or_else(invalid_syntax)?,
+        };
+        let destination = Positions::from_algebraic(square)
+            .ok_or_else(invalid_syntax)?
+            .to_index();
+        if get_legal_drops(board, bitboards, group) & Bitboards::convert_to_bit(destination) == 0 {
+            return Err(illegal_move());
+        }
+        return Ok(NotationMove::new_drop(group, destination));
+    }
+
+    let mut chars: Vec<char> = notation.chars().collect();
+
+    //piece letter, pawns have none
+    let group = match chars.first() {
+        Some(letter @ ('N' | 'B' | 'R' | 'Q' | 'K')) => {
+            let group = piece_group_from_letter(*letter).ok_or_else(invalid_syntax)?;
+            chars.remove(0);
+            group
+        }
+        _ => PieceGroup::Pawn,
+    };
+
+    //promotion, written "e8=Q" or "e8Q", kings only in antichess
+    let mut promotion = None;
+    if let Some(letter) = chars.last().copied()
+        && matches!(letter, 'N' | 'B' | 'R' | 'Q' | 'K')
+    {
+        promotion = piece_group_from_letter(letter);
+        chars.pop();
+        if chars.last() == Some(&'=') {
+            chars.pop();
+        }
+    }
+
+    //destination square is always the last two characters
+    if chars.len() < 2 {
+        return Err(invalid_syntax());
+    }
+    let destination_notation: String = chars.split_off(chars.len() - 2).into_iter().collect();
+    let destination = Positions::from_algebraic(&destination_notation)
+        .ok_or_else(invalid_syntax)?
+        .to_index();
+
+    //whatever is left is disambiguation and capture marker
+    if chars.last() == Some(&'x') {
+        chars.pop();
+    }
+    let (origin_file, origin_rank) = match chars.as_slice() {
+        [] => (None, None),
+        [file @ 'a'..='h'] => (Some(*file as u8 - b'a'), None),
+        [rank @ '1'..='8'] => (None, Some(*rank as u8 - b'1')),
+        [file @ 'a'..='h', rank @ '1'..='8'] => {
+            (Some(*file as u8 - b'a'), Some(*rank as u8 - b'1'))
+        }
+        _ => return Err(invalid_syntax()),
+    };
+
+    let candidates: Vec<u8> = (0..64u8)
+        .filter(|origin| {
+            board.squares[*origin as usize]
+                .is_some_and(|piece| piece.group == group && piece.color == board.turn_color)
+                && legal_moves[*origin as usize] & Bitboards::convert_to_bit(destination) != 0
+                && origin_file.is_none_or(|file| origin % 8 == file)
+                && origin_rank.is_none_or(|rank| origin / 8 == rank)
+        })
+        .collect();
+    let origin = match candidates.as_slice() {
+        [origin] => *origin,
+        [] => return Err(illegal_move()),
+        _ => return Err(NotationError::AmbiguousMove(san.to_string())),
+    };
+
+    //pawns must promote on the last rank and nothing else may
+    let piece = Piece {
+        group,
+        color: board.turn_color,
+        bitboard: 0u64,
+    };
+    if Bitboards::is_promotion_move(piece, destination) != promotion.is_some() {
+        return Err(illegal_move());
+    }
+
+    Ok(NotationMove {
+        origin,
+        destination,
+        promotion,
+        drop: None,
+    })
+}
+
+///Generates the Standard Algebraic Notation for a legal move by the side to move, including check and mate suffixes.
+pub fn generate_san(
+    board: &Board,
+    bitboards: &Bitboards,
+    notation_move: NotationMove,
+) -> Result<String, NotationError> {
+    let NotationMove {
+        origin,
+        destination,
+        promotion,
+        drop,
+    } = notation_move;
+    let illegal_move = || {
+        NotationError::IllegalMove(match drop {
+            Some(group) => format!("{}@{}", piece_letter(group), square_name(destination)),
+            None => format!("{}{}", square_name(origin), square_name(destination)),
+        })
+    };
+
+    let legal_moves = get_legal_moves(board, bitboards);
+    let mut san = String::new();
+    if let Some(group) = drop {
+        if get_legal_drops(board, bitboards, group) & Bitboards::convert_to_bit(destination) == 0 {
+            return Err(illegal_move());
+        }
+        san.push(piece_letter(group));
+        san.push('@');
+        san.push_str(&square_name(destination));
+    } else if let Some(side) = bitboards.get_castling_side(board, origin, destination) {
+        if board.squares[origin as usize].is_none_or(|king| king.color != board.turn_color)
+            || legal_moves[origin as usize] & Bitboards::convert_to_bit(destination) == 0
+        {
+            return Err(illegal_move());
+        }
+        san.push_str(match side {
+            PieceGroup::King => "O-O",
+            _ => "O-O-O",
+        });
+    } else {
+        let piece = board.squares[origin as usize].ok_or_else(illegal_move)?;
+        if piece.color != board.turn_color
+            || legal_moves[origin as usize] & Bitboards::convert_to_bit(destination) == 0
+        {
+            return Err(illegal_move());
+        }
+        let is_capture = board.squares[destination as usize].is_some()
+            || (piece.group == PieceGroup::Pawn && origin % 8 != destination % 8);
+
+        match piece.group {
+            PieceGroup::Pawn => {
+                if is_capture {
+                    san.push(file_letter(origin));
+                }
+            }
+            _ => {
+                san.push(piece_letter(piece.group));
+                san.push_str(&get_disambiguation(
+                    board,
+                    &legal_moves,
+                    piece,
+                    origin,
+                    destination,
+                ));
+            }
+        }
+        if is_capture {
+            san.push('x');
+        }
+        san.push_str(&square_name(destination));
+        if let Some(promotion) = promotion {
+            san.push('=');
+            san.push(piece_letter(promotion));
+        }
+    }
+
+    //play the move on a copy to find check or mate
+    let mut next_board = board.clone();
+    let mut next_bitboards = bitboards.clone();
+    match (drop, promotion) {
+        (Some(group), _) => next_bitboards.drop_piece(&mut next_board, group, destination),
+        (None, Some(promotion)) => {
+            next_bitboards.promote_pawn(&mut next_board, origin, destination, promotion)
+        }
+        (None, None) => next_bitboards.move_piece(&mut next_board, origin, destination),
+    }
+    .map_err(|_| illegal_move())?;
+    next_board.toggle_turn_color();
+    let next_legal_moves = next_bitboards.get_all_legal_moves(&mut next_board);
+    if next_board.variant.has_royal_king() && next_bitboards.is_checked(next_board.turn_color) {
+        san.push(
+            match next_bitboards.get_game_status(&next_board, &next_legal_moves) {
+                GameStatus::Checkmate => '#',
+                _ => '+',
+            },
+        );
+    }
+
+    Ok(san)
+}
+
+///Parses a move in UCI long algebraic notation, e.g. "e2e4", "e7e8q" or the drop "N@f3". Legality, including which pieces a pawn may become, is left to the caller.
+pub fn parse_uci(uci: &str) -> Result<NotationMove, NotationError> {
+    let invalid_syntax = || NotationError::InvalidSyntax(uci.to_string());
+    let notation = uci.trim();
+    if !notation.is_ascii() || !(4..=5).contains(&notation.len()) {
+        return Err(invalid_syntax());
+    }
+
+    if let Some(square) = notation.strip_prefix(|letter: char| letter.is_ascii_alphabetic())
+        && let Some(square) = square.strip_prefix('@')
+    {
+        let group = match notation.as_bytes()[0].to_ascii_uppercase() {
+            b'P' => PieceGroup::Pawn,
+            letter => piece_group_from_letter(letter as char)
+                .filter(|group| *group != PieceGroup::King)
+                .ok_or_else(invalid_syntax)?,
+        };
+        let destination = Positions::from_algebraic(square).ok_or_else(invalid_syntax)?;
+        return Ok(NotationMove::new_drop(group, destination.to_index()));
+    }
+
+    let origin = Positions::from_algebraic(&notation[0..2]).ok_or_else(invalid_syntax)?;
+    let destination = Positions::from_algebraic(&notation[2..4]).ok_or_else(invalid_syntax)?;
+    let promotion = match notation[4..].chars().next() {
+        Some(letter) => {
+            Some(piece_group_from_letter(letter.to_ascii_uppercase()).ok_or_else(invalid_syntax)?)
+        }
+        None => None,
+    };
+
+    Ok(NotationMove {
+        origin: origin.to_index(),
+        destination: destination.to_index(),
+        promotion,
+        drop: None,
+    })
+}
+
+fn get_legal_moves(board: &Board, bitboards: &Bitboards) -> Vec<u64> {
+    let mut board = board.clone();
+    let mut bitboards = bitboards.clone();
+    bitboards.get_all_legal_moves(&mut board)
+}
+
+fn get_legal_drops(board: &Board, bitboards: &Bitboards, group: PieceGroup) -> u64 {
+    let legal_drops = bitboards.clone().get_all_legal_drops(board);
+    POCKET_GROUPS
+        .iter()
+        .position(|pocket_group| *pocket_group == group)
+        .map_or(0, |pocket_index| legal_drops[pocket_index])
+}
+
+///Gets the origin file, rank or square needed to tell this move apart from same-type pieces reaching the same destination.
+fn get_disambiguation(
+    board: &Board,
+    legal_moves: &[u64],
+    piece: Piece,
+    origin: u8,
+    destination: u8,
+) -> String {
+    let rivals: Vec<u8> = (0..64u8)
+        .filter(|other| {
+            *other != origin
+                && board.squares[*other as usize].is_some_and(|other_piece| {
+                    other_piece.group == piece.group && other_piece.color == piece.color
+                })
+                && legal_moves[*other as usize] & Bitboards::convert_to_bit(destination) != 0
+        })
+        .collect();
+
+    if rivals.is_empty() {
+        String::new()
+    } else if rivals.iter().all(|other| other % 8 != origin % 8) {
+        file_letter(origin).to_string()
+    } else if rivals.iter().all(|other| other / 8 != origin / 8) {
+        rank_digit(origin).to_string()
+    } else {
+        square_name(origin)
+    }
+}
+
+fn piece_group_from_letter(letter: char) -> Option<PieceGroup> {
+    match letter {
+        'N' => Some(PieceGroup::Knight),
+        'B' => Some(PieceGroup::Bishop),
+        'R' => Some(PieceGroup::Rook),
+        'Q' => Some(PieceGroup::Queen),
+        'K' => Some(PieceGroup::King),
+        _ => None,
+    }
+}
+
+fn piece_letter(group: PieceGroup) -> char {
+    Piece {
+        group,
+        color: PieceColor::White,
+        bitboard: 0u64,
+    }
+    .to_fen_char()
+}
+
+fn file_letter(square: u8) -> char {
+    (b'a' + square % 8) as char
+}
+
+fn rank_digit(square: u8) -> char {
+    (b'1' + square / 8) as char
+}
+
+fn square_name(square: u8) -> String {
+    format!("{}{}", file_letter(square), rank_digit(square))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::parse_fen;
+    use Positions::*;
+
+    fn notation_move(origin: Positions, destination: Positions) -> NotationMove {
+        NotationMove {
+            origin: origin.to_index(),
+            destination: destination.to_index(),
+            promotion: None,
+            drop: None,
+        }
+    }
+
+    fn san_of(fen: &str, notation_move: NotationMove) -> Result<String, NotationError> {
+        let (board, bitboards) = parse_fen(fen).unwrap();
+        generate_san(&board, &bitboards, notation_move)
+    }
+
+    fn parse(fen: &str, san: &str) -> Result<NotationMove, NotationError> {
+        let (board, bitboards) = parse_fen(fen).unwrap();
+        parse_san(&board, &bitboards, san)
+    }
+
+    #[test]
+    fn disambiguates_by_file_then_rank_then_square() {
+        let knights = "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1";
+        assert_eq!(san_of(knights, notation_move(B1, D2)).unwrap(), "Nbd2");
+        assert_eq!(parse(knights, "Nbd2").unwrap(), notation_move(B1, D2));
+        assert_eq!(parse(knights, "Nfd2").unwrap(), notation_move(F1, D2));
+        assert_eq!(
+            parse(knights, "Nd2").unwrap_err(),
+            NotationError::AmbiguousMove("Nd2".to_string())
+        );
+        //only one knight reaches c3
+        assert_eq!(san_of(knights, notation_move(B1, C3)).unwrap(), "Nc3");
+
+        let rooks = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+        assert_eq!(san_of(rooks, notation_move(A1, A3)).unwrap(), "R1a3");
+        assert_eq!(parse(rooks, "R5a3").unwrap(), notation_move(A5, A3));
+
+        let queens = "4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1";
+        assert_eq!(san_of(queens, notation_move(A1, B2)).unwrap(), "Qa1b2");
+        assert_eq!(san_of(queens, notation_move(C1, B2)).unwrap(), "Qcb2");
+        assert_eq!(san_of(queens, notation_move(A3, B2)).unwrap(), "Q3b2");
+        assert_eq!(parse(queens, "Qa1xb2").unwrap(), notation_move(A1, B2));
+        assert_eq!(
+            parse(queens, "Qab2").unwrap_err(),
+            NotationError::AmbiguousMove("Qab2".to_string())
+        );
+    }
+
+    #[test]
+    fn handles_en_passant_and_promotion() {
+        let en_passant = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2";
+        assert_eq!(san_of(en_passant, notation_move(E5, D6)).unwrap(), "exd6");
+        assert_eq!(
+            parse(en_passant, "exd6 e.p.").unwrap(),
+            notation_move(E5, D6)
+        );
+        assert_eq!(parse(en_passant, "exd6").unwrap(), notation_move(E5, D6));
+
+        let promotion = "8/4P3/8/8/k7/8/8/4K3 w - - 0 1";
+        let queening = NotationMove {
+            promotion: Some(PieceGroup::Queen),
+            ..notation_move(E7, E8)
+        };
+        assert_eq!(san_of(promotion, queening).unwrap(), "e8=Q+");
+        assert_eq!(parse(promotion, "e8=Q+").unwrap(), queening);
+        assert_eq!(parse(promotion, "e8Q").unwrap(), queening);
+        assert_eq!(
+            parse(promotion, "e8").unwrap_err(),
+            NotationError::IllegalMove("e8".to_string())
+        );
+        assert_eq!(
+            parse(promotion, "Ke2=Q").unwrap_err(),
+            NotationError::IllegalMove("Ke2=Q".to_string())
+        );
+    }
+
+    #[test]
+    fn marks_check_and_mate() {
+        let fools_mate = "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2";
+        assert_eq!(san_of(fools_mate, notation_move(D8, H4)).unwrap(), "Qh4#");
+        assert_eq!(san_of(fools_mate, notation_move(F8, B4)).unwrap(), "Bb4");
+        assert_eq!(
+            san_of("4k3/8/8/8/8/8/8/4K2R w K - 0 1", notation_move(H1, H8)).unwrap(),
+            "Rh8+"
+        );
+    }
+
+    #[test]
+    fn castles_only_when_legal() {
+        let castling = "4k3/8/8/8/8/8/8/4K2R w K - 0 1";
+        assert_eq!(san_of(castling, notation_move(E1, G1)).unwrap(), "O-O");
+        assert_eq!(parse(castling, "0-0").unwrap(), notation_move(E1, G1));
+
+        //f1 is attacked, so the king may not pass it
+        let through_check = "4kr2/8/8/8/8/8/8/4K2R w K - 0 1";
+        assert_eq!(
+            san_of(through_check, notation_move(E1, G1)).unwrap_err(),
+            NotationError::IllegalMove("e1g1".to_string())
+        );
+        assert_eq!(
+            parse(through_check, "O-O").unwrap_err(),
+            NotationError::IllegalMove("O-O".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_and_illegal_san() {
+        for san in ["", "Z", "Nz9", "e", "Nbcd2", "Xe4"] {
+            assert_eq!(
+                parse(crate::fen::STARTING_FEN, san).unwrap_err(),
+                NotationError::InvalidSyntax(san.to_string()),
+                "{}",
+                san
+            );
+        }
+        for san in ["e5", "Nd4", "Ke2", "O-O", "exd3"] {
+            assert_eq!(
+                parse(crate::fen::STARTING_FEN, san).unwrap_err(),
+                NotationError::IllegalMove(san.to_string()),
+                "{}",
+                san
+            );
+        }
+        assert_eq!(
+            san_of(crate::fen::STARTING_FEN, notation_move(E7, E5)).unwrap_err(),
+            NotationError::IllegalMove("e7e5".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_uci_moves_and_promotions() {
+        assert_eq!(parse_uci("e2e4").unwrap(), notation_move(E2, E4));
+        assert_eq!(parse_uci(" g1f3\n").unwrap(), notation_move(G1, F3));
+        for (uci, group) in [
+            ("e7e8q", PieceGroup::Queen),
+            ("e7e8r", PieceGroup::Rook),
+            ("e7e8b", PieceGroup::Bishop),
+            ("e7e8N", PieceGroup::Knight),
+            ("e7e8k", PieceGroup::King), //antichess, legality is left to the caller
+        ] {
+            assert_eq!(
+                parse_uci(uci).unwrap(),
+                NotationMove {
+                    promotion: Some(group),
+                    ..notation_move(E7, E8)
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn parses_uci_drops() {
+        assert_eq!(
+            parse_uci("N@f3").unwrap(),
+            NotationMove::new_drop(PieceGroup::Knight, F3.to_index())
+        );
+        assert_eq!(
+            parse_uci("p@e4").unwrap(),
+            NotationMove::new_drop(PieceGroup::Pawn, E4.to_index())
+        );
+        for uci in ["K@e4", "X@e4", "N@i3", "N@f9"] {
+            assert_eq!(
+                parse_uci(uci).unwrap_err(),
+                NotationError::InvalidSyntax(uci.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_uci() {
+        for uci in [
+            "", "e2", "e2e", "e2e4e5", "e2-e4", "e7e8x", "e7e8p", "i2e4", "e2e9", "e0e4", "E2E4",
+            "é2e4",
+        ] {
+            assert_eq!(
+                parse_uci(uci).unwrap_err(),
+                NotationError::InvalidSyntax(uci.to_string()),
+                "{}",
+                uci
+            );
+        }
+    }
+}
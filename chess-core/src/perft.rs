@@ -0,0 +1,312 @@
+use crate::{
+    bitboards::Bitboards,
+    board::Board,
+    move_list::{GenerationMode, Move},
+};
+
+///Counts the leaf nodes of the legal move tree to the given depth, promotions count once per piece choice.
+pub fn perft(board: &Board, bitboards: &Bitboards, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    //generating refreshes the attacks that make_move validates against
+    let mut bitboards = bitboards.clone();
+    let legal_moves = bitboards.generate_legal_moves(board, GenerationMode::All);
+    if depth == 1 {
+        return legal_moves.len() as u64;
+    }
+
+    legal_moves
+        .iter()
+        .map(|legal_move| {
+            let (next_board, next_bitboards) = make_move(board, &bitboards, *legal_move);
+            perft(&next_board, &next_bitboards, depth - 1)
+        })
+        .sum()
+}
+
+///Splits the perft count by root move, each move in UCI notation.
+pub fn divide(board: &Board, bitboards: &Bitboards, depth: u32) -> Vec<(String, u64)> {
+    //generating refreshes the attacks that make_move validates against
+    let mut bitboards = bitboards.clone();
+    let legal_moves = bitboards.generate_legal_moves(board, GenerationMode::All);
+
+    legal_moves
+        .iter()
+        .map(|legal_move| {
+            let (next_board, next_bitboards) = make_move(board, &bitboards, *legal_move);
+            (
+                legal_move.to_uci(),
+                perft(&next_board, &next_bitboards, depth.saturating_sub(1)),
+            )
+        })
+        .collect()
+}
+
+fn make_move(board: &Board, bitboards: &Bitboards, legal_move: Move) -> (Board, Bitboards) {
+    let mut next_board = board.clone();
+    let mut next_bitboards = bitboards.clone();
+    next_bitboards
+        .make_move(&mut next_board, legal_move)
+        .expect("generated moves are legal");
+    next_board.toggle_turn_color();
+
+    (next_board, next_bitboards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fen::{FenError, parse_chess960_fen, parse_fen, parse_variant_fen},
+        variant::Variant,
+    };
+
+    fn assert_perft(fen: &str, expected_nodes: &[u64]) {
+        assert_perft_with(parse_fen, fen, expected_nodes);
+    }
+
+    fn assert_chess960_perft(fen: &str, expected_nodes: &[u64]) {
+        assert_perft_with(parse_chess960_fen, fen, expected_nodes);
+    }
+
+    fn assert_variant_perft(variant: Variant, fen: &str, expected_nodes: &[u64]) {
+        assert_perft_with(
+            |fen| parse_variant_fen(fen, variant, false),
+            fen,
+            expected_nodes,
+        );
+    }
+
+    fn assert_perft_with(
+        parse: impl Fn(&str) -> Result<(Board, Bitboards), FenError>,
+        fen: &str,
+        expected_nodes: &[u64],
+    ) {
+        let (board, bitboards) = parse(fen).unwrap();
+        for (depth, expected) in (1..).zip(expected_nodes) {
+            assert_eq!(
+                perft(&board, &bitboards, depth),
+                *expected,
+                "perft({}) of {}",
+                depth,
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn perft_initial_position() {
+        assert_perft(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[20, 400, 8902, 197281],
+        );
+    }
+
+    #[test]
+    fn perft_kiwipete() {
+        assert_perft(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            &[48, 2039, 97862],
+        );
+    }
+
+    #[test]
+    fn perft_position_3() {
+        assert_perft(
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            &[14, 191, 2812, 43238, 674624],
+        );
+    }
+
+    #[test]
+    fn perft_position_4() {
+        assert_perft(
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            &[6, 264, 9467],
+        );
+    }
+
+    #[test]
+    fn perft_position_4_mirrored() {
+        assert_perft(
+            "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+            &[6, 264, 9467],
+        );
+    }
+
+    #[test]
+    fn perft_position_5() {
+        assert_perft(
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            &[44, 1486, 62379],
+        );
+    }
+
+    #[test]
+    fn perft_position_6() {
+        assert_perft(
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+            &[46, 2079, 89890],
+        );
+    }
+
+    #[test]
+    fn perft_en_passant_discovered_check() {
+        //the captured pawn alone shields the king from the bishop
+        assert_perft(
+            "6b1/8/8/3pP3/8/8/K7/7k w - d6 0 1",
+            &[6, 42, 251, 2519, 15915],
+        );
+        assert_perft(
+            "7K/k7/8/8/3Pp3/8/8/6B1 b - d3 0 1",
+            &[6, 42, 251, 2519, 15915],
+        );
+    }
+
+    #[test]
+    fn perft_chess960_matches_standard_chess() {
+        assert_chess960_perft(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[20, 400, 8902, 197281],
+        );
+        assert_chess960_perft(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w HAha - 0 1",
+            &[48, 2039, 97862],
+        );
+    }
+
+    #[test]
+    fn perft_chess960_positions() {
+        assert_chess960_perft(
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            &[21, 528, 12189, 326672],
+        );
+        assert_chess960_perft(
+            "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+            &[21, 807, 18002, 667366],
+        );
+        assert_chess960_perft(
+            "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9",
+            &[20, 479, 10471, 273318],
+        );
+        assert_chess960_perft(
+            "qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9",
+            &[22, 593, 13440, 382958],
+        );
+        assert_chess960_perft(
+            "1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9",
+            &[28, 1120, 31058, 1171749],
+        );
+    }
+
+    #[test]
+    fn perft_chess960_castling_beside_the_rook() {
+        assert_chess960_perft(
+            "rk5r/8/8/8/8/8/8/R5KR w HAha - 0 1",
+            &[24, 463, 10375, 225591],
+        );
+        assert_chess960_perft(
+            "1rk4r/8/8/8/8/8/8/RK4R1 w GAhb - 0 1",
+            &[3, 72, 1575, 38010],
+        );
+    }
+
+    #[test]
+    fn perft_king_of_the_hill() {
+        assert_variant_perft(
+            Variant::KingOfTheHill,
+            "r1bq1bnr/ppp1kppp/2np4/4p3/3PP3/2NK4/PPP2PPP/R1BQ1BNR w - - 4 6",
+            &[37, 1049, 37094, 1090938],
+        );
+    }
+
+    #[test]
+    fn perft_three_check() {
+        assert_variant_perft(
+            Variant::ThreeCheck,
+            "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR w KQkq - 1+3 4 4",
+            &[42, 1231, 49106, 1447873],
+        );
+    }
+
+    #[test]
+    fn perft_antichess() {
+        assert_variant_perft(
+            Variant::Antichess,
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1",
+            &[20, 400, 8067, 153299],
+        );
+        assert_variant_perft(
+            Variant::Antichess,
+            "rnbqkb1r/p1pppppp/5n2/1p6/8/1P6/P1PPPPPP/RNBQKBNR w - - 0 3",
+            &[21, 394, 5806, 87401],
+        );
+        assert_variant_perft(
+            Variant::Antichess,
+            "8/1P4k1/8/8/2n5/8/5p2/R3K3 w - - 0 1",
+            &[1, 16, 354, 4211],
+        );
+    }
+
+    #[test]
+    fn perft_crazyhouse() {
+        assert_variant_perft(
+            Variant::Crazyhouse,
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1",
+            &[20, 400, 8902, 197281],
+        );
+        assert_variant_perft(
+            Variant::Crazyhouse,
+            "r3k2r/p1pp1pb1/bn2pnp1/3PN3/4P3/5Q2/PPPBBPPP/R3K2R[QPPn] w KQkq - 0 1",
+            &[116, 8112, 842178],
+        );
+        //the promoted queen on g7 goes back to the pocket as a pawn
+        assert_variant_perft(
+            Variant::Crazyhouse,
+            "4k3/1P4q~1/8/8/8/8/1p6/R3K3[Pn] b Q - 0 1",
+            &[91, 4925, 249904],
+        );
+    }
+
+    #[test]
+    fn perft_atomic() {
+        assert_variant_perft(
+            Variant::Atomic,
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[20, 400, 8902, 197326],
+        );
+        assert_variant_perft(
+            Variant::Atomic,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            &[48, 1939, 88298],
+        );
+        assert_variant_perft(
+            Variant::Atomic,
+            "r3k2r/8/8/2q5/8/8/8/R3K2R w KQkq - 0 1",
+            &[23, 1097, 21233, 913780],
+        );
+    }
+
+    #[test]
+    fn perft_atomic_kings_side_by_side() {
+        //squares beside the black king are safe in atomic, so white may castle through them
+        assert_variant_perft(
+            Variant::Atomic,
+            "8/8/8/8/8/8/3k4/R3K2R w KQ - 0 1",
+            &[25, 143, 3927, 22594],
+        );
+        assert_variant_perft(
+            Variant::Atomic,
+            "4k3/8/8/8/2q5/8/3K4/8 w - - 0 1",
+            &[3, 90, 307, 8522],
+        );
+        //the en passant capture explodes on c6, not on the captured pawn's square
+        assert_variant_perft(
+            Variant::Atomic,
+            "8/1k6/8/KPp5/8/8/8/8 w - c6 0 2",
+            &[5, 28, 125, 686],
+        );
+    }
+}
@@ -0,0 +1,651 @@
+use crate::{
+    chess960::STANDARD_POSITION_INDEX,
+    fen::FenError,
+    game::Game,
+    notation::parse_san,
+    piece::PieceColor,
+    status::{GameStatus, VariantWin},
+    variant::Variant,
+};
+use std::fmt;
+
+const MAX_LINE_LENGTH: usize = 79;
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+///Generates the PGN of a game: the seven tag roster, any custom tags, then numbered SAN movetext ending in the result.
+pub fn generate_pgn(game: &Game) -> String {
+    //a game the board hasn't decided keeps the result it was given, e.g. by resignation or adjudication
+    let result = match get_result_token(game.get_status(), game.board.turn_color) {
+        "*" => game
+            .tags
+            .iter()
+            .find(|(name, _)| name == "Result")
+            .and_then(|(_, value)| {
+                ["1-0", "0-1", "1/2-1/2"]
+                    .into_iter()
+                    .find(|result| result == value)
+            })
+            .unwrap_or("*"),
+        result => result,
+    };
+    let mut pgn = String::new();
+
+    //seven tag roster comes first and in order, Result always matches the movetext
+    for (name, default_value) in SEVEN_TAG_ROSTER {
+        let value = match name {
+            "Result" => result,
+            _ => game
+                .tags
+                .iter()
+                .find(|(tag_name, _)| tag_name == name)
+                .map_or(default_value, |(_, value)| value.as_str()),
+        };
+        pgn.push_str(&format_tag(name, value));
+    }
+    if let Some(starting_fen) = &game.starting_fen {
+        pgn.push_str(&format_tag("SetUp", "1"));
+        pgn.push_str(&format_tag("FEN", starting_fen));
+    }
+    let variant_tag = match (game.board.variant, game.board.is_chess960) {
+        (Variant::Standard, false) => None,
+        (Variant::Standard, true) => Some("Chess960"),
+        (variant, _) => Some(variant.get_name()),
+    };
+    if let Some(variant_tag) = variant_tag {
+        pgn.push_str(&format_tag("Variant", variant_tag));
+    }
+    for (name, value) in &game.tags {
+        let is_generated = SEVEN_TAG_ROSTER
+            .iter()
+            .any(|(roster_name, _)| roster_name == name)
+            || name == "SetUp"
+            || name == "FEN"
+            || (name == "Variant" && variant_tag.is_some());
+        if !is_generated {
+            pgn.push_str(&format_tag(name, value));
+        }
+    }
+    pgn.push('\n');
+
+    let mut tokens = vec![];
+    for (index, played_move) in game.move_history.iter().enumerate() {
+        let record = played_move.record;
+        match record.piece.color {
+            PieceColor::White => tokens.push(format!("{}.", record.prior_fullmove_number)),
+            PieceColor::Black if index == 0 => {
+                tokens.push(format!("{}...", record.prior_fullmove_number))
+            }
+            PieceColor::Black => {}
+        }
+        tokens.push(played_move.san.clone());
+    }
+    tokens.push(result.to_string());
+
+    pgn.push_str(&wrap_tokens(&tokens));
+    pgn.push('\n');
+    pgn
+}
+
+fn get_result_token(status: GameStatus, turn_color: PieceColor) -> &'static str {
+    match status {
+        //side to move is the one mated
+        GameStatus::Checkmate => match turn_color {
+            PieceColor::White => "0-1",
+            PieceColor::Black => "1-0",
+        },
+        GameStatus::VariantWin(VariantWin { winner, .. }) => match winner {
+            PieceColor::White => "1-0",
+            PieceColor::Black => "0-1",
+        },
+        GameStatus::Stalemate | GameStatus::Draw(_) => "1/2-1/2",
+        GameStatus::Ongoing | GameStatus::Check => "*",
+    }
+}
+
+fn format_tag(name: &str, value: &str) -> String {
+    let escaped_value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("[{} \"{}\"]\n", name, escaped_value)
+}
+
+///Joins movetext tokens with spaces, breaking lines before they exceed MAX_LINE_LENGTH.
+fn wrap_tokens(tokens: &[String]) -> String {
+    let mut lines = vec![];
+    let mut line = String::new();
+
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE_LENGTH {
+            lines.push(line);
+            line = String::new();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(token);
+    }
+    lines.push(line);
+
+    lines.join("\n")
+}
+
+///A game read from PGN text before its moves are replayed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<String>,
+    pub result: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PgnError {
+    UnterminatedTag(usize),
+    UnterminatedComment(usize),
+    UnbalancedVariation(usize),
+    InvalidFen {
+        game: usize,
+        error: FenError,
+    },
+    IllegalMove {
+        game: usize,
+        move_number: u32,
+        color: PieceColor,
+        san: String,
+        error: String,
+    },
+    UnsupportedVariant {
+        game: usize,
+        variant: String,
+    },
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PgnError::UnterminatedTag(line) => write!(f, "tag on line {} is missing ']'", line),
+            PgnError::UnterminatedComment(line) => {
+                write!(f, "comment starting on line {} is missing '}}'", line)
+            }
+            PgnError::UnbalancedVariation(line) => {
+                write!(f, "variation parentheses on line {} are unbalanced", line)
+            }
+            PgnError::InvalidFen { game, error } => {
+                write!(f, "game {} has an invalid FEN tag: {}", game, error)
+            }
+            PgnError::IllegalMove {
+                game,
+                move_number,
+                color,
+                san,
+                error,
+            } => {
+                let dots = match color {
+                    PieceColor::White => ".",
+                    PieceColor::Black => "...",
+                };
+                write!(
+                    f,
+                    "game {}, move {}{} {}: {}",
+                    game, move_number, dots, san, error
+                )
+            }
+            PgnError::UnsupportedVariant { game, variant } => {
+                write!(
+                    f,
+                    "game {} is played under unsupported variant '{}'",
+                    game, variant
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+///Splits PGN text into its games, dropping comments, NAGs, move numbers and variations.
+pub fn parse_pgn(pgn: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut games = vec![];
+    let mut current = PgnGame {
+        tags: vec![],
+        moves: vec![],
+        result: None,
+    };
+    let chars: Vec<char> = pgn.chars().collect();
+    let mut index = 0;
+    let mut line = 1;
+    let mut is_line_start = true;
+
+    while index < chars.len() {
+        let character = chars[index];
+
+        if character == '\n' {
+            line += 1;
+            is_line_start = true;
+            index += 1;
+            continue;
+        }
+        if character.is_whitespace() {
+            index += 1;
+            continue;
+        }
+        if is_line_start && character == '%' {
+            //escaped line
+            while index < chars.len() && chars[index] != '\n' {
+                index += 1;
+            }
+            continue;
+        }
+        is_line_start = false;
+
+        match character {
+            '[' => {
+                if !current.moves.is_empty() {
+                    //tags after movetext start the next game
+                    games.push(current);
+                    current = PgnGame {
+                        tags: vec![],
+                        moves: vec![],
+                        result: None,
+                    };
+                }
+                let start_line = line;
+                let start = index + 1;
+                let mut is_in_quotes = false;
+                loop {
+                    index += 1;
+                    match chars.get(index) {
+                        Some('\\') if is_in_quotes => index += 1,
+                        Some('"') => is_in_quotes = !is_in_quotes,
+                        Some(']') if !is_in_quotes => break,
+                        Some('\n') | None => return Err(PgnError::UnterminatedTag(start_line)),
+                        Some(_) => {}
+                    }
+                }
+                current.tags.push(parse_tag(&chars[start..index]));
+                index += 1;
+            }
+            '{' => {
+                let start_line = line;
+                loop {
+                    match chars.get(index) {
+                        Some('}') => break,
+                        Some('\n') => line += 1,
+                        Some(_) => {}
+                        None => return Err(PgnError::UnterminatedComment(start_line)),
+                    }
+                    index += 1;
+                }
+                index += 1;
+            }
+            ';' => {
+                while index < chars.len() && chars[index] != '\n' {
+                    index += 1;
+                }
+            }
+            '(' => {
+                let start_line = line;
+                let mut depth = 0;
+                loop {
+                    match chars.get(index) {
+                        Some('(') => depth += 1,
+                        Some(')') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        Some('{') => {
+                            //comments inside variations may hold parentheses
+                            while index < chars.len() && chars[index] != '}' {
+                                if chars[index] == '\n' {
+                                    line += 1;
+                                }
+                                index += 1;
+                            }
+                        }
+                        Some('\n') => line += 1,
+                        Some(_) => {}
+                        None => return Err(PgnError::UnbalancedVariation(start_line)),
+                    }
+                    index += 1;
+                }
+                index += 1;
+            }
+            ')' => return Err(PgnError::UnbalancedVariation(line)),
+            _ => {
+                let start = index;
+                while index < chars.len()
+                    && !chars[index].is_whitespace()
+                    && !matches!(chars[index], '{' | '(' | ')' | ';' | '[')
+                {
+                    index += 1;
+                }
+                let token: String = chars[start..index].iter().collect();
+
+                match token.as_str() {
+                    "1-0" | "0-1" | "1/2-1/2" | "*" => {
+                        current.result = Some(token);
+                        games.push(current);
+                        current = PgnGame {
+                            tags: vec![],
+                            moves: vec![],
+                            result: None,
+                        };
+                    }
+                    _ if token.starts_with('$') => {} //numeric annotation glyph
+                    _ => {
+                        //move numbers may be attached to the move, e.g. "12.e4", but "0-0" is a castle
+                        let after_digits =
+                            token.trim_start_matches(|letter: char| letter.is_ascii_digit());
+                        let san = match after_digits.is_empty() || after_digits.starts_with('.') {
+                            true => after_digits.trim_start_matches('.'),
+                            false => token.as_str(),
+                        }
+                        .trim_end_matches(['!', '?']);
+                        if !san.is_empty() {
+                            current.moves.push(san.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if !current.tags.is_empty() || !current.moves.is_empty() {
+        games.push(current);
+    }
+
+    Ok(games)
+}
+
+///Splits the inside of a tag pair, e.g. `White "Morphy, Paul"`, into its name and unescaped value.
+fn parse_tag(tag: &[char]) -> (String, String) {
+    let name: String = tag
+        .iter()
+        .skip_while(|letter| letter.is_whitespace())
+        .take_while(|letter| !letter.is_whitespace() && **letter != '"')
+        .collect();
+
+    let mut value = String::new();
+    let mut letters = tag.iter().skip_while(|letter| **letter != '"').skip(1);
+    while let Some(&letter) = letters.next() {
+        match letter {
+            '\\' => {
+                if let Some(&escaped) = letters.next() {
+                    value.push(escaped);
+                }
+            }
+            '"' => break,
+            _ => value.push(letter),
+        }
+    }
+
+    (name, value)
+}
+
+///Replays a parsed PGN game move by move from its starting position, failing on the first illegal move.
+pub fn replay_pgn_game(pgn_game: &PgnGame, game_number: usize) -> Result<Game, PgnError> {
+    let starting_fen = pgn_game
+        .tags
+        .iter()
+        .find(|(name, _)| name == "FEN")
+        .map(|(_, fen)| fen);
+    let variant_tag = pgn_game
+        .tags
+        .iter()
+        .find(|(name, _)| name == "Variant")
+        .map(|(_, variant)| variant.to_lowercase());
+    let (variant, is_chess960) = match variant_tag.as_deref() {
+        None => (Variant::Standard, false),
+        Some("chess960" | "chess 960" | "fischerandom") => (Variant::Standard, true),
+        Some(name) => match Variant::from_name(name) {
+            Some(variant) => (variant, false),
+            None => {
+                return Err(PgnError::UnsupportedVariant {
+                    game: game_number,
+                    variant: name.to_string(),
+                });
+            }
+        },
+    };
+    let mut game = match (starting_fen, is_chess960) {
+        (Some(fen), _) => Game::from_variant_fen(fen, variant, is_chess960).map_err(|error| {
+            PgnError::InvalidFen {
+                game: game_number,
+                error,
+            }
+        })?,
+        (None, true) => Game::new_chess960(STANDARD_POSITION_INDEX).unwrap_or_default(),
+        (None, false) => Game::new_variant(variant),
+    };
+    game.tags = pgn_game.tags.clone();
+
+    for san in &pgn_game.moves {
+        let (move_number, color) = (game.board.fullmove_number, game.board.turn_color);
+        let illegal_move = |error: String| PgnError::IllegalMove {
+            game: game_number,
+            move_number,
+            color,
+            san: san.clone(),
+            error,
+        };
+        let notation_move = parse_san(&game.board, &game.bitboards, san)
+            .map_err(|error| illegal_move(error.to_string()))?;
+        game.make_notation_move(notation_move)
+            .map_err(|error| illegal_move(error.to_string()))?;
+    }
+
+    Ok(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fen::generate_fen, position::Positions, test_support::play};
+
+    #[test]
+    fn writes_the_seven_tag_roster_first_and_escapes_values() {
+        let mut game = Game::new();
+        game.tags = vec![
+            ("Annotator".to_string(), "C:\\games".to_string()),
+            ("White".to_string(), "Morphy, \"Paul\"".to_string()),
+            ("Event".to_string(), "Paris".to_string()),
+        ];
+        play(&mut game, &[(Positions::E2, Positions::E4)]);
+
+        assert_eq!(
+            generate_pgn(&game),
+            "[Event \"Paris\"]\n\
+             [Site \"?\"]\n\
+             [Date \"????.??.??\"]\n\
+             [Round \"?\"]\n\
+             [White \"Morphy, \\\"Paul\\\"\"]\n\
+             [Black \"?\"]\n\
+             [Result \"*\"]\n\
+             [Annotator \"C:\\\\games\"]\n\
+             \n\
+             1. e4 *\n"
+        );
+
+        //escaped values read back unchanged
+        let games = parse_pgn(&generate_pgn(&game)).unwrap();
+        assert!(
+            games[0]
+                .tags
+                .contains(&("White".to_string(), "Morphy, \"Paul\"".to_string()))
+        );
+        assert!(
+            games[0]
+                .tags
+                .contains(&("Annotator".to_string(), "C:\\games".to_string()))
+        );
+    }
+
+    #[test]
+    fn wraps_movetext_at_79_columns() {
+        let mut game = Game::new();
+        let shuffle = [
+            (Positions::G1, Positions::F3),
+            (Positions::G8, Positions::F6),
+            (Positions::F3, Positions::G1),
+            (Positions::F6, Positions::G8),
+        ];
+        for _ in 0..4 {
+            play(&mut game, &shuffle);
+        }
+        let pgn = generate_pgn(&game);
+        let movetext: Vec<&str> = pgn
+            .lines()
+            .skip_while(|line| !line.is_empty())
+            .skip(1)
+            .collect();
+
+        assert!(movetext.len() > 1);
+        assert!(movetext.iter().all(|line| line.len() <= MAX_LINE_LENGTH));
+        //lines break between tokens, never inside one
+        assert_eq!(
+            movetext.join(" "),
+            "1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3 Nf6 4. Ng1 Ng8 5. Nf3 Nf6 6. Ng1 Ng8 7. Nf3 Nf6 8. Ng1 Ng8 1/2-1/2"
+        );
+    }
+
+    #[test]
+    fn result_token_follows_the_game_or_its_result_tag() {
+        let mut game = Game::new();
+        play(
+            &mut game,
+            &[
+                (Positions::F2, Positions::F3),
+                (Positions::E7, Positions::E5),
+                (Positions::G2, Positions::G4),
+                (Positions::D8, Positions::H4),
+            ],
+        );
+        let pgn = generate_pgn(&game);
+        assert!(pgn.contains("[Result \"0-1\"]"));
+        assert!(pgn.ends_with("2. g4 Qh4# 0-1\n"));
+
+        //an undecided board keeps the result it was given
+        let mut game = Game::new();
+        game.tags = vec![("Result".to_string(), "1-0".to_string())];
+        play(&mut game, &[(Positions::E2, Positions::E4)]);
+        let pgn = generate_pgn(&game);
+        assert!(pgn.contains("[Result \"1-0\"]"));
+        assert!(pgn.ends_with("1. e4 1-0\n"));
+
+        //as does one imported from a decided PGN
+        let games = parse_pgn("[Result \"1/2-1/2\"]\n\n1. d4 d5 1/2-1/2").unwrap();
+        let game = replay_pgn_game(&games[0], 1).unwrap();
+        assert!(generate_pgn(&game).ends_with("1. d4 d5 1/2-1/2\n"));
+
+        //but the board's own result wins over the tag
+        let mut game = Game::new();
+        game.tags = vec![("Result".to_string(), "1-0".to_string())];
+        play(
+            &mut game,
+            &[
+                (Positions::F2, Positions::F3),
+                (Positions::E7, Positions::E5),
+                (Positions::G2, Positions::G4),
+                (Positions::D8, Positions::H4),
+            ],
+        );
+        assert!(generate_pgn(&game).ends_with("Qh4# 0-1\n"));
+    }
+
+    #[test]
+    fn parses_past_comments_nags_and_variations() {
+        let pgn = "[Event \"Casual\"]\n\
+            [White \"Anderssen, \\\"The Immortal\\\"\"]\n\
+            % escaped line (\n\
+            \n\
+            1. e4 {a comment (with parentheses)} e5 $1 2.Nf3 (2. f4 exf4 (2... d5) {nested} 3. Nf3) \n\
+            2... Nc6!? ; rest of the line ( is a comment\n\
+            3. Bb5 1-0\n";
+        let games = parse_pgn(pgn).unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(
+            games[0].tags,
+            vec![
+                ("Event".to_string(), "Casual".to_string()),
+                (
+                    "White".to_string(),
+                    "Anderssen, \"The Immortal\"".to_string()
+                ),
+            ]
+        );
+        assert_eq!(games[0].moves, vec!["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+        assert_eq!(games[0].result.as_deref(), Some("1-0"));
+
+        assert_eq!(
+            parse_pgn("1. e4 (1. d4").unwrap_err(),
+            PgnError::UnbalancedVariation(1)
+        );
+        assert_eq!(
+            parse_pgn("1. e4\n{unfinished").unwrap_err(),
+            PgnError::UnterminatedComment(2)
+        );
+        assert_eq!(
+            parse_pgn("[Event \"x\"\n1. e4").unwrap_err(),
+            PgnError::UnterminatedTag(1)
+        );
+    }
+
+    #[test]
+    fn splits_multi_game_files() {
+        let pgn = "[Event \"First\"]\n\n1. e4 e5 1/2-1/2\n\n\
+            [Event \"Second\"]\n\n1. d4 *\n\n\
+            [Event \"Third\"]\n\n1. c4";
+        let games = parse_pgn(pgn).unwrap();
+        assert_eq!(games.len(), 3);
+        assert_eq!(games[0].moves, vec!["e4", "e5"]);
+        assert_eq!(games[1].moves, vec!["d4"]);
+        assert_eq!(games[1].result.as_deref(), Some("*"));
+        assert_eq!(games[2].moves, vec!["c4"]);
+        assert_eq!(games[2].result, None);
+    }
+
+    #[test]
+    fn replays_zero_castling_and_bare_move_numbers() {
+        let pgn = "1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 d6 5 d3 Bg4 6.Nc3 Qd7 7.Be3 0-0-0 *";
+        let games = parse_pgn(pgn).unwrap();
+        assert_eq!(games[0].moves[6], "0-0");
+        assert_eq!(games[0].moves[8], "d3");
+        let game = replay_pgn_game(&games[0], 1).unwrap();
+        assert_eq!(
+            generate_fen(&game.board, &game.bitboards),
+            "2kr2nr/pppq1ppp/2np4/2b1p3/2B1P1b1/2NPBN2/PPP2PPP/R2Q1RK1 w - - 5 8"
+        );
+    }
+
+    #[test]
+    fn reports_the_move_number_of_an_illegal_move() {
+        let games = parse_pgn("1. e4 e5 2. Nf3 Nf3 *").unwrap();
+        let error = replay_pgn_game(&games[0], 3).err().unwrap();
+        assert!(matches!(
+            error,
+            PgnError::IllegalMove {
+                game: 3,
+                move_number: 2,
+                color: PieceColor::Black,
+                ..
+            }
+        ));
+        assert!(error.to_string().starts_with("game 3, move 2... Nf3:"));
+
+        let games = parse_pgn("[Variant \"Horde\"]\n1. e4 *").unwrap();
+        assert_eq!(
+            replay_pgn_game(&games[0], 1).err(),
+            Some(PgnError::UnsupportedVariant {
+                game: 1,
+                variant: "horde".to_string()
+            })
+        );
+    }
+}
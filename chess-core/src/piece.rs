@@ -90,7 +90,7 @@ impl Piece {
         ]
     }
 
-    pub fn to_index(&self) -> usize {
+    pub fn to_index(self) -> usize {
         Piece::to_piece_index(self.color, self.group)
     }
 
@@ -178,6 +178,44 @@ impl Piece {
         }
     }
 
+    ///Returns the FEN letter for the piece, uppercase for White and lowercase for Black.
+    pub fn to_fen_char(self) -> char {
+        let letter = match self.group {
+            PieceGroup::Pawn => 'p',
+            PieceGroup::Knight => 'n',
+            PieceGroup::Bishop => 'b',
+            PieceGroup::Rook => 'r',
+            PieceGroup::Queen => 'q',
+            PieceGroup::King => 'k',
+        };
+        match self.color {
+            PieceColor::White => letter.to_ascii_uppercase(),
+            PieceColor::Black => letter,
+        }
+    }
+
+    ///Parses a FEN letter, uppercase for White and lowercase for Black.
+    pub fn from_fen_char(letter: char) -> Option<Piece> {
+        let group = match letter.to_ascii_lowercase() {
+            'p' => PieceGroup::Pawn,
+            'n' => PieceGroup::Knight,
+            'b' => PieceGroup::Bishop,
+            'r' => PieceGroup::Rook,
+            'q' => PieceGroup::Queen,
+            'k' => PieceGroup::King,
+            _ => return None,
+        };
+        let color = match letter.is_ascii_uppercase() {
+            true => PieceColor::White,
+            false => PieceColor::Black,
+        };
+        Some(Piece {
+            group,
+            color,
+            bitboard: 0u64,
+        })
+    }
+
     pub fn color_to_index(piece_color: PieceColor) -> usize {
         match piece_color {
             PieceColor::White => 0,
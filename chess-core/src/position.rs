@@ -70,8 +70,27 @@ pub enum Positions {
 }
 
 impl Positions {
-    pub fn to_index(&self) -> u8 {
-        *self as u8
+    pub fn to_index(self) -> u8 {
+        self as u8
+    }
+
+    ///Returns the square's algebraic name, e.g. "e4".
+    pub fn to_algebraic(self) -> String {
+        let index = self.to_index();
+        let file = (b'a' + index % 8) as char;
+        let rank = (b'1' + index / 8) as char;
+        format!("{}{}", file, rank)
+    }
+
+    ///Parses an algebraic square name, e.g. "e4".
+    pub fn from_algebraic(square: &str) -> Option<Positions> {
+        let mut chars = square.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(file @ 'a'..='h'), Some(rank @ '1'..='8'), None) => {
+                Positions::from_index((rank as u8 - b'1') * 8 + (file as u8 - b'a'))
+            }
+            _ => None,
+        }
     }
 
     pub fn from_index(index: u8) -> Option<Positions> {
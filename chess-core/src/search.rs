@@ -0,0 +1,702 @@
+use crate::{
+    bitboards::Bitboards,
+    board::Board,
+    evaluation::{EvalParams, evaluate},
+    move_list::{GenerationMode, Move, MoveFlag},
+    move_record::MoveRecord,
+    piece::PieceGroup,
+    status::{GameStatus, VariantWin},
+};
+use std::{
+    cmp::Reverse,
+    mem,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+pub const MATE_SCORE: i32 = 30_000; //mated at ply n scores -MATE_SCORE + n
+pub const MAX_PLY: usize = 64;
+const INFINITY: i32 = 32_000;
+const MATE_THRESHOLD: i32 = MATE_SCORE - MAX_PLY as i32;
+const DEFAULT_TABLE_ENTRIES: usize = 1 << 18;
+const TIME_CHECK_INTERVAL: u64 = 2048; //nodes between clock and stop signal reads
+
+///Bounds a search, whichever limit is reached first stops it. Without limits it searches to MAX_PLY.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+///The outcome of the deepest completed iteration, score is from the side to move's view in centipawns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best_move: Option<Move>, //None when the side to move has no legal moves
+    pub score: i32,
+    pub depth: u32,
+    pub nodes: u64,
+    pub principal_variation: Vec<Move>,
+}
+
+impl SearchResult {
+    ///Gets the moves until mate, positive when the side to move mates and negative when it is mated.
+    pub fn mate_in(&self) -> Option<i32> {
+        if self.score.abs() < MATE_THRESHOLD {
+            return None;
+        }
+        let plies = MATE_SCORE - self.score.abs();
+        match self.score > 0 {
+            true => Some((plies + 1) / 2),
+            false => Some(-(plies / 2)),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower, //failed high, the score is at least this
+    Upper, //failed low, the score is at most this
+}
+
+#[derive(Copy, Clone, Debug)]
+struct TableEntry {
+    key: u64,
+    depth: u32,
+    score: i32,
+    bound: Bound,
+    best_move: Option<Move>,
+}
+
+///Fixed-size hash table of searched positions keyed by Zobrist hash, deeper results replace shallower ones.
+pub struct TranspositionTable {
+    entries: Vec<Option<TableEntry>>,
+}
+
+impl TranspositionTable {
+    ///Creates a table with at least the given number of entries, rounded up to a power of two.
+    pub fn new(entries: usize) -> Self {
+        Self {
+            entries: vec![None; entries.max(1).next_power_of_two()],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+
+    fn get_index(&self, key: u64) -> usize {
+        key as usize & (self.entries.len() - 1)
+    }
+
+    fn probe(&self, key: u64) -> Option<TableEntry> {
+        self.entries[self.get_index(key)].filter(|entry| entry.key == key)
+    }
+
+    fn store(&mut self, entry: TableEntry) {
+        let index = self.get_index(entry.key);
+        let is_replaceable = match self.entries[index] {
+            Some(existing) => existing.key != entry.key || entry.depth >= existing.depth,
+            None => true,
+        };
+        if is_replaceable {
+            self.entries[index] = Some(entry);
+        }
+    }
+}
+
+///Negamax alpha-beta searcher, keeps its transposition table between searches.
+pub struct Searcher {
+    table: TranspositionTable,
+    params: EvalParams,
+    killers: [[Option<Move>; 2]; MAX_PLY], //quiet moves that caused a cutoff, per ply
+    history: [[i32; 64]; 64],              //cutoff counts by origin and destination
+    limits: SearchLimits,
+    start: Instant,
+    nodes: u64,
+    can_stop: bool, //the first iteration always completes so there is a move to return
+    is_stopped: bool,
+    stop_signal: Arc<AtomicBool>, //set from another thread to end the search early
+    root_best_move: Option<Move>, //kept outside the table so a collision can't lose it
+}
+
+impl Searcher {
+    pub fn new() -> Self {
+        Searcher::with_table_entries(DEFAULT_TABLE_ENTRIES)
+    }
+
+    pub fn with_table_entries(entries: usize) -> Self {
+        Self {
+            table: TranspositionTable::new(entries),
+            params: EvalParams::default(),
+            killers: [[None; 2]; MAX_PLY],
+            history: [[0; 64]; 64],
+            limits: SearchLimits::default(),
+            start: Instant::now(),
+            nodes: 0,
+            can_stop: false,
+            is_stopped: false,
+            stop_signal: Arc::new(AtomicBool::new(false)),
+            root_best_move: None,
+        }
+    }
+
+    ///Resizes the transposition table to fit in the given memory, dropping its entries.
+    pub fn set_table_size_mb(&mut self, megabytes: usize) {
+        let entry_size = mem::size_of::<Option<TableEntry>>();
+        //new rounds up to a power of two, round down first so the table stays within the budget
+        let entries = (megabytes * 1024 * 1024 / entry_size).max(1);
+        self.table = TranspositionTable::new(1 << entries.ilog2());
+    }
+
+    ///Gets the flag that stops a running search when set, the caller clears it before the next search.
+    pub fn get_stop_signal(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop_signal)
+    }
+
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+
+    ///Replaces the evaluation weights, clearing the table since its scores came from the old ones.
+    pub fn set_params(&mut self, params: EvalParams) {
+        self.params = params;
+        self.table.clear();
+    }
+
+    ///Searches the position with iterative deepening until a limit is reached or a forced mate is found.
+    pub fn search(
+        &mut self,
+        board: &Board,
+        bitboards: &Bitboards,
+        limits: SearchLimits,
+    ) -> SearchResult {
+        self.search_with_progress(board, bitboards, limits, |_| {})
+    }
+
+    ///Searches like search, calling on_iteration with the result of every completed depth.
+    pub fn search_with_progress(
+        &mut self,
+        board: &Board,
+        bitboards: &Bitboards,
+        limits: SearchLimits,
+        mut on_iteration: impl FnMut(&SearchResult),
+    ) -> SearchResult {
+        self.killers = [[None; 2]; MAX_PLY];
+        self.history = [[0; 64]; 64];
+        self.limits = limits;
+        self.start = Instant::now();
+        self.nodes = 0;
+        self.can_stop = false;
+        self.is_stopped = false;
+        self.root_best_move = None;
+
+        let mut board = board.clone();
+        let max_depth = limits
+            .depth
+            .unwrap_or(MAX_PLY as u32)
+            .clamp(1, MAX_PLY as u32 - 1);
+        let mut result = SearchResult {
+            best_move: None,
+            score: 0,
+            depth: 0,
+            nodes: 0,
+            principal_variation: vec![],
+        };
+
+        for depth in 1..=max_depth {
+            let score = self.negamax(&mut board, bitboards, depth, 0, -INFINITY, INFINITY);
+            if self.is_stopped {
+                //an interrupted iteration may not have looked at the best move yet
+                break;
+            }
+
+            let principal_variation = self.get_principal_variation(&mut board, bitboards, depth);
+            result = SearchResult {
+                best_move: self.root_best_move,
+                score,
+                depth,
+                nodes: self.nodes,
+                principal_variation,
+            };
+            on_iteration(&result);
+            self.can_stop = true;
+            if score.abs() >= MATE_THRESHOLD || result.best_move.is_none() {
+                break;
+            }
+        }
+
+        result.nodes = self.nodes;
+        result
+    }
+
+    fn negamax(
+        &mut self,
+        board: &mut Board,
+        bitboards: &Bitboards,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        if self.should_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        if ply > 0 && (board.halfmove_clock >= 100 || is_repetition(board)) {
+            return 0;
+        }
+        if depth == 0 || ply >= MAX_PLY - 1 {
+            return self.quiescence(board, bitboards, ply, alpha, beta);
+        }
+
+        let entry = self.table.probe(board.zobrist_hash);
+        if ply > 0
+            && let Some(entry) = entry
+            && entry.depth >= depth
+        {
+            let score = score_from_table(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => return score,
+                Bound::Lower if score >= beta => return score,
+                Bound::Upper if score <= alpha => return score,
+                _ => {}
+            }
+        }
+
+        //generating refreshes the attacks that make_move validates against
+        let mut bitboards = bitboards.clone();
+        let mut moves = bitboards.generate_legal_moves(board, GenerationMode::All);
+        if moves.is_empty() {
+            return get_terminal_score(board, &bitboards, ply);
+        }
+
+        let table_move = entry.and_then(|entry| entry.best_move);
+        moves.sort_by_cached_key(|ordered_move| {
+            Reverse(self.score_move(board, *ordered_move, table_move, ply))
+        });
+
+        let original_alpha = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+        for searched_move in moves.iter().copied() {
+            let (mut child_bitboards, move_record) = make_move(board, &bitboards, searched_move);
+            let score = -self.negamax(board, &child_bitboards, depth - 1, ply + 1, -beta, -alpha);
+            unmake_move(board, &mut child_bitboards, &move_record);
+            if self.is_stopped {
+                return 0;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(searched_move);
+                alpha = alpha.max(score);
+            }
+            if alpha >= beta {
+                if !searched_move.is_capture() {
+                    self.update_quiet_cutoff(searched_move, depth, ply);
+                }
+                break;
+            }
+        }
+
+        if ply == 0 {
+            self.root_best_move = best_move;
+        }
+        let bound = if best_score >= beta {
+            Bound::Lower
+        } else if best_score > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        self.table.store(TableEntry {
+            key: board.zobrist_hash,
+            depth,
+            score: score_to_table(best_score, ply),
+            bound,
+            best_move,
+        });
+
+        best_score
+    }
+
+    ///Searches captures, or every evasion when in check, until the position is quiet, so the static evaluation isn't taken mid-exchange.
+    fn quiescence(
+        &mut self,
+        board: &mut Board,
+        bitboards: &Bitboards,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        if self.should_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+
+        if board.variant.get_position_win(board, bitboards).is_some() {
+            return get_terminal_score(board, bitboards, ply);
+        }
+
+        let stand_pat = evaluate(bitboards, &self.params).score_for(board.turn_color);
+        if ply >= MAX_PLY - 1 {
+            return stand_pat;
+        }
+
+        let mut bitboards = bitboards.clone();
+        let mut moves = bitboards.generate_legal_moves(board, GenerationMode::Captures);
+        let is_in_check = board.variant.has_royal_king() && bitboards.is_checked(board.turn_color);
+        if is_in_check {
+            //a king in check can't stand pat, every evasion is searched instead
+            moves = bitboards.generate_legal_moves(board, GenerationMode::All);
+            if moves.is_empty() {
+                return get_terminal_score(board, &bitboards, ply);
+            }
+        } else if !board.variant.is_capture_forced() || moves.is_empty() {
+            //standing pat, the side to move can usually do at least as well as doing nothing, unless a capture is forced
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+        }
+        moves.sort_by_cached_key(|ordered_move| Reverse(get_mvv_lva_score(board, *ordered_move)));
+
+        for searched_move in moves.iter().copied() {
+            let (mut child_bitboards, move_record) = make_move(board, &bitboards, searched_move);
+            let score = -self.quiescence(board, &child_bitboards, ply + 1, -beta, -alpha);
+            unmake_move(board, &mut child_bitboards, &move_record);
+            if self.is_stopped {
+                return 0;
+            }
+
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+
+        alpha
+    }
+
+    ///Orders the table move first, then captures by MVV-LVA, queen promotions, killers and finally history.
+    fn score_move(
+        &self,
+        board: &Board,
+        scored_move: Move,
+        table_move: Option<Move>,
+        ply: usize,
+    ) -> i32 {
+        if Some(scored_move) == table_move {
+            return 1_000_000;
+        }
+        if scored_move.is_capture() {
+            return 500_000 + get_mvv_lva_score(board, scored_move);
+        }
+        if scored_move.promotion() == Some(PieceGroup::Queen) {
+            return 400_000;
+        }
+        match self.killers[ply] {
+            [Some(killer), _] if killer == scored_move => 300_000,
+            [_, Some(killer)] if killer == scored_move => 200_000,
+            _ => self.history[scored_move.origin() as usize][scored_move.destination() as usize]
+                .min(100_000),
+        }
+    }
+
+    fn update_quiet_cutoff(&mut self, cutoff_move: Move, depth: u32, ply: usize) {
+        let killers = &mut self.killers[ply];
+        if killers[0] != Some(cutoff_move) {
+            killers[1] = killers[0];
+            killers[0] = Some(cutoff_move);
+        }
+        self.history[cutoff_move.origin() as usize][cutoff_move.destination() as usize] +=
+            (depth * depth) as i32;
+    }
+
+    fn should_stop(&mut self) -> bool {
+        if self.is_stopped || !self.can_stop {
+            return self.is_stopped;
+        }
+        let is_over_nodes = self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes);
+        let is_checkpoint = self.nodes.is_multiple_of(TIME_CHECK_INTERVAL);
+        let is_over_time = is_checkpoint
+            && self
+                .limits
+                .time
+                .is_some_and(|time| self.start.elapsed() >= time);
+        let is_signalled = is_checkpoint && self.stop_signal.load(Ordering::Relaxed);
+        self.is_stopped = is_over_nodes || is_over_time || is_signalled;
+        self.is_stopped
+    }
+
+    ///Follows best moves through the table from the root best move, checking each is legal since entries may have been overwritten.
+    fn get_principal_variation(
+        &self,
+        board: &mut Board,
+        bitboards: &Bitboards,
+        depth: u32,
+    ) -> Vec<Move> {
+        let mut principal_variation = vec![];
+        let mut played = vec![];
+        let mut bitboards = bitboards.clone();
+
+        while principal_variation.len() < depth as usize {
+            let best_move = match principal_variation.is_empty() {
+                true => self.root_best_move,
+                false => self
+                    .table
+                    .probe(board.zobrist_hash)
+                    .and_then(|entry| entry.best_move),
+            };
+            let Some(best_move) = best_move else {
+                break;
+            };
+            let legal_moves = bitboards.generate_legal_moves(board, GenerationMode::All);
+            if !legal_moves.contains(&best_move) {
+                break;
+            }
+
+            let (child_bitboards, move_record) = make_move(board, &bitboards, best_move);
+            played.push((bitboards, move_record));
+            bitboards = child_bitboards;
+            principal_variation.push(best_move);
+            if is_repetition(board) {
+                break;
+            }
+        }
+
+        //restore the board for the caller
+        while let Some((parent_bitboards, move_record)) = played.pop() {
+            unmake_move(board, &mut bitboards, &move_record);
+            bitboards = parent_bitboards;
+        }
+        principal_variation
+    }
+}
+
+impl Default for Searcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///Plays the move on the board and a copy of the bitboards, the copy is needed to unmake it.
+fn make_move(
+    board: &mut Board,
+    bitboards: &Bitboards,
+    played_move: Move,
+) -> (Bitboards, MoveRecord) {
+    let mut child_bitboards = bitboards.clone();
+    let move_record = child_bitboards
+        .make_move(board, played_move)
+        .expect("generated moves are legal");
+    board.toggle_turn_color();
+    (child_bitboards, move_record)
+}
+
+fn unmake_move(board: &mut Board, child_bitboards: &mut Bitboards, move_record: &MoveRecord) {
+    child_bitboards.unmake_move(board, move_record);
+    board.toggle_turn_color();
+}
+
+///Checks if the position already occurred since the last capture or pawn move, a single repeat scores as a draw.
+fn is_repetition(board: &Board) -> bool {
+    board
+        .position_history
+        .iter()
+        .rev()
+        .take(board.halfmove_clock as usize)
+        .any(|previous_hash| *previous_hash == board.zobrist_hash)
+}
+
+//mate scores are stored relative to the node so they stay correct when reached through another path
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score >= MATE_THRESHOLD => score + ply as i32,
+        score if score <= -MATE_THRESHOLD => score - ply as i32,
+        score => score,
+    }
+}
+
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score >= MATE_THRESHOLD => score - ply as i32,
+        score if score <= -MATE_THRESHOLD => score + ply as i32,
+        score => score,
+    }
+}
+
+///Most valuable victim first, least valuable attacker breaking ties.
+fn get_mvv_lva_score(board: &Board, capture: Move) -> i32 {
+    let victim = match capture.flag() {
+        MoveFlag::EnPassant => Some(PieceGroup::Pawn),
+        _ => board.squares[capture.destination() as usize].map(|piece| piece.group),
+    };
+    let attacker = board.squares[capture.origin() as usize].map(|piece| piece.group);
+    victim.map_or(0, get_piece_value) * 10 - attacker.map_or(0, get_piece_value)
+}
+
+fn get_piece_value(group: PieceGroup) -> i32 {
+    match group {
+        PieceGroup::Pawn => 100,
+        PieceGroup::Knight => 320,
+        PieceGroup::Bishop => 330,
+        PieceGroup::Rook => 500,
+        PieceGroup::Queen => 900,
+        PieceGroup::King => 0,
+    }
+}
+
+///Scores a position the side to move can't play on from, mate or a variant win sooner scoring further from zero.
+fn get_terminal_score(board: &Board, bitboards: &Bitboards, ply: usize) -> i32 {
+    match bitboards.get_game_status(board, &[]) {
+        GameStatus::Checkmate => -MATE_SCORE + ply as i32,
+        GameStatus::VariantWin(VariantWin { winner, .. }) if winner == board.turn_color => {
+            MATE_SCORE - ply as i32
+        }
+        GameStatus::VariantWin(_) => -MATE_SCORE + ply as i32,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fen::parse_fen, position::Positions};
+
+    fn search_fen(fen: &str, limits: SearchLimits) -> SearchResult {
+        let (board, bitboards) = parse_fen(fen).unwrap();
+        Searcher::with_table_entries(1 << 14).search(&board, &bitboards, limits)
+    }
+
+    fn depth(depth: u32) -> SearchLimits {
+        SearchLimits {
+            depth: Some(depth),
+            ..SearchLimits::default()
+        }
+    }
+
+    fn squares(searched_move: Option<Move>) -> Option<(Positions, Positions)> {
+        searched_move.and_then(|searched_move| {
+            Some((
+                Positions::from_index(searched_move.origin())?,
+                Positions::from_index(searched_move.destination())?,
+            ))
+        })
+    }
+
+    #[test]
+    fn finds_mate_in_one() {
+        let back_rank = search_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1", depth(3));
+        assert_eq!(
+            squares(back_rank.best_move),
+            Some((Positions::A1, Positions::A8))
+        );
+        assert_eq!(back_rank.mate_in(), Some(1));
+
+        let scholars = search_fen(
+            "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+            depth(3),
+        );
+        assert_eq!(
+            squares(scholars.best_move),
+            Some((Positions::H5, Positions::F7))
+        );
+    }
+
+    #[test]
+    fn plays_for_variant_wins() {
+        //a lone king walking to the center beats the extra queen
+        let (board, bitboards) = crate::fen::parse_variant_fen(
+            "q7/8/8/8/8/2K5/8/7k w - - 0 1",
+            crate::variant::Variant::KingOfTheHill,
+            false,
+        )
+        .unwrap();
+        let result = Searcher::with_table_entries(1 << 14).search(&board, &bitboards, depth(3));
+        assert_eq!(
+            squares(result.best_move),
+            Some((Positions::C3, Positions::D4))
+        );
+        assert_eq!(result.mate_in(), Some(1));
+    }
+
+    #[test]
+    fn sees_being_mated() {
+        //Kg8 is forced and Ra8 mates
+        let result = search_fen("7k/8/6K1/8/8/8/8/R7 b - - 0 1", depth(4));
+        assert!(result.best_move.is_some());
+        assert_eq!(result.mate_in(), Some(-1));
+    }
+
+    #[test]
+    fn wins_hanging_queen() {
+        let result = search_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", depth(2));
+        assert_eq!(
+            squares(result.best_move),
+            Some((Positions::D2, Positions::D5))
+        );
+        assert!(result.score > 400);
+    }
+
+    #[test]
+    fn no_move_when_game_is_over() {
+        let stalemate = search_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", depth(3));
+        assert_eq!(stalemate.best_move, None);
+        assert_eq!(stalemate.score, 0);
+    }
+
+    #[test]
+    fn quiescence_does_not_stand_pat_when_it_must_move() {
+        let mut searcher = Searcher::with_table_entries(1 << 14);
+        //mated, standing pat would score the extra rook
+        let (mut board, bitboards) = parse_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+        assert_eq!(
+            searcher.quiescence(&mut board, &bitboards, 0, -INFINITY, INFINITY),
+            -MATE_SCORE
+        );
+
+        //the queen has to take on d7 and is taken back
+        let (mut board, bitboards) = crate::fen::parse_variant_fen(
+            "3r3k/3p4/8/8/8/8/8/3Q3K w - - 0 1",
+            crate::variant::Variant::Antichess,
+            false,
+        )
+        .unwrap();
+        assert!(searcher.quiescence(&mut board, &bitboards, 0, -INFINITY, INFINITY) < 0);
+    }
+
+    #[test]
+    fn node_limit_stops_search() {
+        let result = search_fen(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            SearchLimits {
+                nodes: Some(5_000),
+                ..SearchLimits::default()
+            },
+        );
+        assert!(result.best_move.is_some());
+        assert!(result.depth >= 1 && result.depth < MAX_PLY as u32 - 1);
+        //the first iteration always completes, later ones stop at the limit
+        assert!(result.nodes <= 5_000 || result.depth == 1);
+    }
+
+    #[test]
+    fn principal_variation_starts_with_best_move() {
+        let result = search_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            depth(3),
+        );
+        assert_eq!(
+            result.principal_variation.first().copied(),
+            result.best_move
+        );
+        assert!(result.principal_variation.len() <= 3);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::piece::PieceColor;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameStatus {
+    Ongoing,
+    Check,
+    Checkmate,
+    Stalemate,
+    Draw(DrawReason),
+    VariantWin(VariantWin),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DrawReason {
+    FiftyMoveRule,
+    SeventyFiveMoveRule,
+    ThreefoldRepetition,
+    FivefoldRepetition,
+    InsufficientMaterial,
+}
+
+///A win by a variant's own rule rather than checkmate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VariantWin {
+    pub winner: PieceColor,
+    pub reason: WinReason,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WinReason {
+    KingOfTheHill,
+    ThreeChecks,
+    NoPiecesLeft, //antichess, the winner gave away every piece
+    NoMovesLeft,  //antichess, the winner has pieces but can't move them
+    KingExploded, //atomic, the loser's king was caught in a capture's explosion
+}
+
+impl GameStatus {
+    pub fn is_game_over(self) -> bool {
+        match self {
+            GameStatus::Ongoing | GameStatus::Check => false,
+            GameStatus::Checkmate
+            | GameStatus::Stalemate
+            | GameStatus::Draw(_)
+            | GameStatus::VariantWin(_) => true,
+        }
+    }
+}
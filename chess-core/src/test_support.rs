@@ -0,0 +1,9 @@
+use crate::{game::Game, position::Positions};
+
+///Plays each move for whichever side is to move, panicking if one is rejected.
+pub fn play(game: &mut Game, moves: &[(Positions, Positions)]) {
+    for (origin, destination) in moves {
+        game.make_move(origin.to_index(), destination.to_index(), None)
+            .unwrap();
+    }
+}
@@ -0,0 +1,544 @@
+use crate::{
+    bitboards::Bitboards,
+    board::Board,
+    piece::{Piece, PieceColor, PieceGroup},
+    status::{GameStatus, VariantWin, WinReason},
+};
+use serde::{Deserialize, Serialize};
+
+const CENTER_SQUARES: u64 = 0x0000_0018_1800_0000; //d4, e4, d5 and e5
+const THREE_CHECK_LIMIT: u8 = 3;
+const STANDARD_PROMOTIONS: [PieceGroup; 4] = [
+    PieceGroup::Queen,
+    PieceGroup::Rook,
+    PieceGroup::Bishop,
+    PieceGroup::Knight,
+];
+const ANTICHESS_PROMOTIONS: [PieceGroup; 5] = [
+    PieceGroup::Queen,
+    PieceGroup::Rook,
+    PieceGroup::Bishop,
+    PieceGroup::Knight,
+    PieceGroup::King,
+];
+
+///The rule set a game is played under, standard chess being one of them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Variant {
+    #[default]
+    Standard,
+    KingOfTheHill, //bringing the king to the center also wins
+    ThreeCheck,    //giving a third check also wins
+    Antichess,     //captures are forced and losing every piece wins
+    Crazyhouse,    //captured pieces can be dropped back on the board by the capturer
+    Atomic,        //captures explode the pieces around them and exploding the king wins
+}
+
+impl Variant {
+    pub const ALL: [Variant; 6] = [
+        Variant::Standard,
+        Variant::KingOfTheHill,
+        Variant::ThreeCheck,
+        Variant::Antichess,
+        Variant::Crazyhouse,
+        Variant::Atomic,
+    ];
+
+    ///Gets the name used for the variant in the PGN Variant tag.
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Variant::Standard => "Standard",
+            Variant::KingOfTheHill => "King of the Hill",
+            Variant::ThreeCheck => "Three-check",
+            Variant::Antichess => "Antichess",
+            Variant::Crazyhouse => "Crazyhouse",
+            Variant::Atomic => "Atomic",
+        }
+    }
+
+    ///Finds the variant for a PGN Variant tag, ignoring case, spaces and dashes.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name: String = name
+            .chars()
+            .filter(|letter| letter.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match name.as_str() {
+            "standard" | "chess" | "fromposition" => Some(Variant::Standard),
+            "kingofthehill" | "koth" => Some(Variant::KingOfTheHill),
+            "threecheck" | "3check" => Some(Variant::ThreeCheck),
+            "antichess" | "giveaway" | "suicide" => Some(Variant::Antichess),
+            "crazyhouse" | "zh" => Some(Variant::Crazyhouse),
+            "atomic" => Some(Variant::Atomic),
+            _ => None,
+        }
+    }
+
+    ///Checks if the king may not be left in check, false where it is captured like any other piece.
+    pub fn has_royal_king(self) -> bool {
+        self != Variant::Antichess
+    }
+
+    pub fn allows_castling(self) -> bool {
+        self != Variant::Antichess
+    }
+
+    ///Checks if a capture must be played whenever one is available.
+    pub fn is_capture_forced(self) -> bool {
+        self == Variant::Antichess
+    }
+
+    ///Checks if checks given are counted toward a win.
+    pub fn counts_checks(self) -> bool {
+        self == Variant::ThreeCheck
+    }
+
+    ///Checks if captured pieces go to the capturer's pocket to be dropped later.
+    pub fn has_drops(self) -> bool {
+        self == Variant::Crazyhouse
+    }
+
+    ///Checks if captures explode, taking the capturer and every piece but pawns around the capture square off the board.
+    pub fn has_explosions(self) -> bool {
+        self == Variant::Atomic
+    }
+
+    ///Gets the pieces a pawn may promote to.
+    pub fn get_promotion_groups(self) -> &'static [PieceGroup] {
+        match self {
+            Variant::Antichess => &ANTICHESS_PROMOTIONS,
+            _ => &STANDARD_PROMOTIONS,
+        }
+    }
+
+    ///Gets the win the position already holds regardless of the moves left, a king on the hill or a third check.
+    pub fn get_position_win(self, board: &Board, bitboards: &Bitboards) -> Option<VariantWin> {
+        [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .find_map(|color| {
+                let reason = match self {
+                    Variant::KingOfTheHill => {
+                        let king =
+                            bitboards.all_pieces[Piece::to_piece_index(color, PieceGroup::King)];
+                        (king & CENTER_SQUARES != 0).then_some(WinReason::KingOfTheHill)
+                    }
+                    Variant::ThreeCheck => (board.checks_given[Piece::color_to_index(color)]
+                        >= THREE_CHECK_LIMIT)
+                        .then_some(WinReason::ThreeChecks),
+                    Variant::Atomic => {
+                        let enemy_king = bitboards.all_pieces[Piece::to_piece_index(
+                            Piece::get_opposite_color(color),
+                            PieceGroup::King,
+                        )];
+                        (enemy_king == 0).then_some(WinReason::KingExploded)
+                    }
+                    Variant::Standard | Variant::Antichess | Variant::Crazyhouse => None,
+                }?;
+                Some(VariantWin {
+                    winner: color,
+                    reason,
+                })
+            })
+    }
+
+    ///Gets the status when the side to move has no legal moves.
+    pub fn get_no_moves_status(
+        self,
+        board: &Board,
+        bitboards: &Bitboards,
+        is_checked: bool,
+    ) -> GameStatus {
+        if self == Variant::Antichess {
+            let own_pieces = bitboards.all_pieces[match board.turn_color {
+                PieceColor::White => 0..6,
+                PieceColor::Black => 6..12,
+            }]
+            .iter()
+            .any(|pieces| *pieces != 0);
+            return GameStatus::VariantWin(VariantWin {
+                winner: board.turn_color,
+                reason: match own_pieces {
+                    true => WinReason::NoMovesLeft,
+                    false => WinReason::NoPiecesLeft,
+                },
+            });
+        }
+        match is_checked {
+            true => GameStatus::Checkmate,
+            false => GameStatus::Stalemate,
+        }
+    }
+
+    ///Checks if neither side can win any more, the standard material rules only hold where mate is the one way to win.
+    pub fn is_insufficient_material(self, board: &Board, bitboards: &Bitboards) -> bool {
+        match self {
+            Variant::Standard => bitboards.is_insufficient_material(),
+            //any piece can still give a check
+            Variant::ThreeCheck => {
+                bitboards
+                    .all_pieces
+                    .iter()
+                    .map(|pieces| pieces.count_ones())
+                    .sum::<u32>()
+                    <= 2
+            }
+            //a lone king can still walk to the center or be given away
+            Variant::KingOfTheHill | Variant::Antichess => false,
+            //captured pieces come back, only two bare kings with empty pockets can't mate
+            Variant::Crazyhouse => {
+                board.pockets.iter().flatten().all(|count| *count == 0)
+                    && bitboards
+                        .all_pieces
+                        .iter()
+                        .map(|pieces| pieces.count_ones())
+                        .sum::<u32>()
+                        <= 2
+            }
+            Variant::Atomic => [PieceColor::White, PieceColor::Black]
+                .into_iter()
+                .all(|color| Variant::cannot_explode_king(bitboards, color)),
+        }
+    }
+
+    ///Checks if the color can never explode the enemy king in atomic, by a bare king or lone minor pieces.
+    fn cannot_explode_king(bitboards: &Bitboards, color: PieceColor) -> bool {
+        let pieces = |color: PieceColor, group: PieceGroup| {
+            bitboards.all_pieces[Piece::to_piece_index(color, group)]
+        };
+        let both_colors =
+            |group: PieceGroup| pieces(PieceColor::White, group) | pieces(PieceColor::Black, group);
+        let enemy_color = Piece::get_opposite_color(color);
+        let kings = both_colors(PieceGroup::King);
+        let occupancy = bitboards
+            .all_pieces
+            .iter()
+            .fold(0u64, |occupancy, pieces| occupancy | pieces);
+        let color_occupancy = |color: PieceColor| {
+            bitboards.all_pieces[match color {
+                PieceColor::White => 0..6,
+                PieceColor::Black => 6..12,
+            }]
+            .iter()
+            .fold(0u64, |occupancy, pieces| occupancy | pieces)
+        };
+
+        if color_occupancy(color) & !kings == 0 {
+            //a bare king can't capture anything
+            return true;
+        }
+        if color_occupancy(enemy_color) & !kings != 0 {
+            //an enemy piece beside its king can be exploded, unless only bishops that never meet are left
+            let bishops = both_colors(PieceGroup::Bishop);
+            if occupancy != kings | bishops {
+                return false;
+            }
+            let light_squares = 0x55AA_55AA_55AA_55AAu64;
+            let own_bishops = pieces(color, PieceGroup::Bishop);
+            let enemy_bishops = pieces(enemy_color, PieceGroup::Bishop);
+            return (own_bishops & !light_squares == 0 && enemy_bishops & light_squares == 0)
+                || (own_bishops & light_squares == 0 && enemy_bishops & !light_squares == 0);
+        }
+        if both_colors(PieceGroup::Queen)
+            | both_colors(PieceGroup::Rook)
+            | both_colors(PieceGroup::Pawn)
+            != 0
+        {
+            //a queen or rook can mate a bare king, and pawns can promote to one
+            return false;
+        }
+        let minors = both_colors(PieceGroup::Knight) | both_colors(PieceGroup::Bishop);
+        if minors.count_ones() == 1 {
+            return true;
+        }
+        //two knights can't mate a bare king either
+        let knights = both_colors(PieceGroup::Knight);
+        occupancy == kings | knights && knights.count_ones() <= 2
+    }
+
+    ///Gets the remaining checks field written into Three-check FENs, e.g. "3+3" before any check.
+    pub fn get_remaining_checks_field(self, board: &Board) -> Option<String> {
+        match self {
+            Variant::ThreeCheck => Some(format!(
+                "{}+{}",
+                THREE_CHECK_LIMIT.saturating_sub(board.checks_given[0]),
+                THREE_CHECK_LIMIT.saturating_sub(board.checks_given[1])
+            )),
+            _ => None,
+        }
+    }
+
+    ///Parses a Three-check remaining checks field like "3+2" into the checks each side has given.
+    pub fn parse_remaining_checks_field(field: &str) -> Option<[u8; 2]> {
+        let (white, black) = field.split_once('+')?;
+        let checks_given = |remaining: &str| {
+            remaining
+                .parse::<u8>()
+                .ok()
+                .filter(|remaining| *remaining <= THREE_CHECK_LIMIT)
+                .map(|remaining| THREE_CHECK_LIMIT - remaining)
+        };
+        Some([checks_given(white)?, checks_given(black)?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fen::{generate_fen, parse_variant_fen},
+        game::Game,
+        notation::{NotationMove, parse_san, parse_uci},
+        position::Positions::*,
+        status::DrawReason,
+        test_support::play,
+    };
+
+    #[test]
+    fn names_round_trip() {
+        for variant in Variant::ALL {
+            assert_eq!(Variant::from_name(variant.get_name()), Some(variant));
+        }
+        assert_eq!(
+            Variant::from_name("kingOfTheHill"),
+            Some(Variant::KingOfTheHill)
+        );
+        assert_eq!(Variant::from_name("Horde"), None);
+    }
+
+    #[test]
+    fn king_of_the_hill_is_won_in_the_center() {
+        let mut game = Game::from_variant_fen(
+            "4k3/8/8/8/8/4K3/8/8 w - - 0 1",
+            Variant::KingOfTheHill,
+            false,
+        )
+        .unwrap();
+        play(&mut game, &[(E3, E4)]);
+        assert_eq!(
+            game.get_status(),
+            GameStatus::VariantWin(VariantWin {
+                winner: PieceColor::White,
+                reason: WinReason::KingOfTheHill,
+            })
+        );
+        assert_eq!(
+            game.make_move(E8.to_index(), E7.to_index(), None)
+                .unwrap_err(),
+            crate::MoveError::GameOver
+        );
+    }
+
+    #[test]
+    fn three_check_counts_checks_until_the_third() {
+        let mut game =
+            Game::from_variant_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", Variant::ThreeCheck, false)
+                .unwrap();
+        play(&mut game, &[(A1, A8), (E8, E7), (A8, A7)]);
+        assert_eq!(game.board.checks_given, [2, 0]);
+        assert_eq!(
+            generate_fen(&game.board, &game.bitboards),
+            "8/R3k3/8/8/8/8/8/4K3 b - - 1+3 3 2"
+        );
+        game.undo_move();
+        assert_eq!(game.board.checks_given, [1, 0]);
+        game.redo_move();
+        assert_eq!(game.get_status(), GameStatus::Check);
+
+        play(&mut game, &[(E7, E6), (A7, A6)]);
+        assert_eq!(
+            game.get_status(),
+            GameStatus::VariantWin(VariantWin {
+                winner: PieceColor::White,
+                reason: WinReason::ThreeChecks,
+            })
+        );
+
+        let (board, _) = parse_variant_fen(
+            "4k3/8/8/8/8/8/8/R3K3 w - - 1+2 0 1",
+            Variant::ThreeCheck,
+            false,
+        )
+        .unwrap();
+        assert_eq!(board.checks_given, [2, 1]);
+    }
+
+    #[test]
+    fn antichess_forces_captures_and_is_won_by_losing_everything() {
+        let mut game = Game::new_variant(Variant::Antichess);
+        play(&mut game, &[(E2, E4), (D7, D5)]);
+        //the capture is forced, so the king may not wander off
+        assert!(game.make_move(E1.to_index(), E2.to_index(), None).is_err());
+        play(&mut game, &[(E4, D5)]);
+
+        let mut game =
+            Game::from_variant_fen("8/8/8/8/8/8/1p6/N7 b - - 0 1", Variant::Antichess, false)
+                .unwrap();
+        game.make_move(B2.to_index(), A1.to_index(), Some(PieceGroup::King))
+            .unwrap();
+        assert_eq!(
+            game.get_status(),
+            GameStatus::VariantWin(VariantWin {
+                winner: PieceColor::White,
+                reason: WinReason::NoPiecesLeft,
+            })
+        );
+    }
+
+    #[test]
+    fn crazyhouse_pockets_captures_to_drop_them() {
+        let mut game = Game::new_variant(Variant::Crazyhouse);
+        play(&mut game, &[(E2, E4), (D7, D5), (E4, D5), (D8, D5)]);
+        assert_eq!(game.board.pockets, [[1, 0, 0, 0, 0], [1, 0, 0, 0, 0]]);
+        assert_eq!(
+            game.drop_piece(PieceGroup::Knight, F3.to_index())
+                .unwrap_err(),
+            crate::MoveError::PieceNotInPocket(PieceGroup::Knight)
+        );
+
+        let pawn_drop = NotationMove::new_drop(PieceGroup::Pawn, E4.to_index());
+        assert_eq!(
+            parse_san(&game.board, &game.bitboards, "@e4"),
+            Ok(pawn_drop)
+        );
+        assert_eq!(parse_uci("P@e4"), Ok(pawn_drop));
+
+        let played_move = game.make_notation_move(pawn_drop).unwrap();
+        assert_eq!(played_move.san, "P@e4");
+        let fen = "rnb1kbnr/ppp1pppp/8/3q4/4P3/8/PPPP1PPP/RNBQKBNR[p] b KQkq - 0 3";
+        assert_eq!(generate_fen(&game.board, &game.bitboards), fen);
+        game.undo_move();
+        assert_eq!(
+            game.board
+                .get_pocket_count(PieceColor::White, PieceGroup::Pawn),
+            1
+        );
+        game.redo_move();
+        assert_eq!(generate_fen(&game.board, &game.bitboards), fen);
+    }
+
+    #[test]
+    fn crazyhouse_drops_must_block_checks_and_keep_pawns_off_the_back_ranks() {
+        let mut game = Game::from_variant_fen(
+            "4r1k1/8/8/8/8/8/8/4K3[Pn] w - - 0 1",
+            Variant::Crazyhouse,
+            false,
+        )
+        .unwrap();
+        let pawn_drops = game.bitboards.clone().get_all_legal_drops(&game.board)[0];
+        assert_eq!(pawn_drops, 0x0010_1010_1010_1000); //e2 to e7
+        assert_eq!(
+            game.drop_piece(PieceGroup::Pawn, A3.to_index())
+                .unwrap_err(),
+            crate::MoveError::KingLeftInCheck
+        );
+        game.drop_piece(PieceGroup::Pawn, E2.to_index()).unwrap();
+        assert_eq!(
+            game.drop_piece(PieceGroup::Knight, G8.to_index())
+                .unwrap_err(),
+            crate::MoveError::IllegalDestination(G8.to_index())
+        );
+
+        let mut game = Game::from_variant_fen(
+            "4k3/8/8/8/8/8/8/4K3[P] w - - 0 1",
+            Variant::Crazyhouse,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            game.drop_piece(PieceGroup::Pawn, A8.to_index())
+                .unwrap_err(),
+            crate::MoveError::IllegalDestination(A8.to_index())
+        );
+    }
+
+    #[test]
+    fn crazyhouse_promoted_pieces_are_pocketed_as_pawns() {
+        let mut game = Game::from_variant_fen(
+            "2rk4/1P6/8/8/8/8/8/4K3[] w - - 0 1",
+            Variant::Crazyhouse,
+            false,
+        )
+        .unwrap();
+        game.make_move(B7.to_index(), C8.to_index(), Some(PieceGroup::Queen))
+            .unwrap();
+        assert_eq!(
+            generate_fen(&game.board, &game.bitboards),
+            "2Q~k4/8/8/8/8/8/8/4K3[R] b - - 0 1"
+        );
+        play(&mut game, &[(D8, C8)]);
+        assert_eq!(
+            generate_fen(&game.board, &game.bitboards),
+            "2k5/8/8/8/8/8/8/4K3[Rp] w - - 0 2"
+        );
+
+        //pockets may also be written as a ninth rank
+        let (board, _) = parse_variant_fen(
+            "2k5/8/8/8/8/8/8/4K3/Rp w - - 0 2",
+            Variant::Crazyhouse,
+            false,
+        )
+        .unwrap();
+        assert_eq!(board.pockets, game.board.pockets);
+    }
+
+    #[test]
+    fn atomic_captures_explode_and_exploding_the_king_wins() {
+        let fen = "4k3/3pq3/8/8/8/8/8/3QK3 w - - 0 1";
+        let mut game = Game::from_variant_fen(fen, Variant::Atomic, false).unwrap();
+        assert_eq!(game.get_status(), GameStatus::Check);
+        //taking on d7 explodes the checking queen and the king beside it
+        play(&mut game, &[(D1, D7)]);
+        assert_eq!(
+            generate_fen(&game.board, &game.bitboards),
+            "8/8/8/8/8/8/8/4K3 b - - 0 1"
+        );
+        assert_eq!(
+            game.get_status(),
+            GameStatus::VariantWin(VariantWin {
+                winner: PieceColor::White,
+                reason: WinReason::KingExploded,
+            })
+        );
+        game.undo_move();
+        assert_eq!(generate_fen(&game.board, &game.bitboards), fen);
+    }
+
+    #[test]
+    fn atomic_kings_cannot_capture_or_check_each_other() {
+        let mut game =
+            Game::from_variant_fen("8/8/8/8/8/8/3kq3/4K3 w - - 0 1", Variant::Atomic, false)
+                .unwrap();
+        assert_eq!(game.get_status(), GameStatus::Ongoing);
+        assert_eq!(
+            game.make_move(E1.to_index(), E2.to_index(), None)
+                .unwrap_err(),
+            crate::MoveError::IllegalDestination(E2.to_index())
+        );
+        //stepping away from the black king lets the queen check
+        assert!(game.make_move(E1.to_index(), F1.to_index(), None).is_err());
+        play(&mut game, &[(E1, D1)]);
+    }
+
+    #[test]
+    fn atomic_draws_only_when_no_side_can_explode_the_king() {
+        for (fen, is_draw) in [
+            ("4k3/8/8/8/8/8/8/4K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/8/1N2K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1", true),
+            //a rook or queen against a bare king can still mate
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", false),
+            ("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", false),
+            ("4k3/8/8/8/8/8/8/r3K3 w - - 0 1", false),
+        ] {
+            let game = Game::from_variant_fen(fen, Variant::Atomic, false).unwrap();
+            assert_eq!(
+                game.get_status() == GameStatus::Draw(DrawReason::InsufficientMaterial),
+                is_draw,
+                "{}",
+                fen
+            );
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{
+    board::Board,
+    piece::{Piece, PieceColor, PieceGroup},
+};
+
+///Random keys xored together to form a position's Zobrist hash.
+pub struct ZobristKeys {
+    pieces: [[u64; 64]; 12], //same index order as Bitboards::all_pieces
+    black_to_move: u64,
+    castling: [u64; 4], //white kingside, white queenside, black kingside, black queenside
+    en_passant_files: [u64; 8],
+    pockets: [[u64; 5]; 2], //mixed with the piece count, same order as Board::pockets
+    checks_given: [u64; 2], //mixed with the check count, same order as Board::checks_given
+    promoted_squares: [u64; 64],
+}
+
+pub static ZOBRIST_KEYS: ZobristKeys = ZobristKeys::generate(0x2545_F491_4F6C_DD1D);
+
+impl ZobristKeys {
+    ///Fills every key from a splitmix64 sequence, so hashes are stable across runs and builds.
+    const fn generate(seed: u64) -> Self {
+        let mut state = seed;
+        let mut pieces = [[0u64; 64]; 12];
+        let mut piece_index = 0;
+        while piece_index < 12 {
+            let mut square = 0;
+            while square < 64 {
+                state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+                pieces[piece_index][square] = ZobristKeys::mix(state);
+                square += 1;
+            }
+            piece_index += 1;
+        }
+
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let black_to_move = ZobristKeys::mix(state);
+
+        let mut castling = [0u64; 4];
+        let mut right = 0;
+        while right < 4 {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            castling[right] = ZobristKeys::mix(state);
+            right += 1;
+        }
+
+        let mut en_passant_files = [0u64; 8];
+        let mut file = 0;
+        while file < 8 {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            en_passant_files[file] = ZobristKeys::mix(state);
+            file += 1;
+        }
+
+        let mut pockets = [[0u64; 5]; 2];
+        let mut color_index = 0;
+        while color_index < 2 {
+            let mut pocket_index = 0;
+            while pocket_index < 5 {
+                state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+                pockets[color_index][pocket_index] = ZobristKeys::mix(state);
+                pocket_index += 1;
+            }
+            color_index += 1;
+        }
+
+        let mut checks_given = [0u64; 2];
+        color_index = 0;
+        while color_index < 2 {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            checks_given[color_index] = ZobristKeys::mix(state);
+            color_index += 1;
+        }
+
+        let mut promoted_squares = [0u64; 64];
+        let mut square = 0;
+        while square < 64 {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            promoted_squares[square] = ZobristKeys::mix(state);
+            square += 1;
+        }
+
+        Self {
+            pieces,
+            black_to_move,
+            castling,
+            en_passant_files,
+            pockets,
+            checks_given,
+            promoted_squares,
+        }
+    }
+
+    pub(crate) const fn mix(state: u64) -> u64 {
+        let mut mixed = state;
+        mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        mixed ^ (mixed >> 31)
+    }
+
+    pub fn piece_key(&self, piece: Piece, square: u8) -> u64 {
+        self.pieces[piece.to_index()][square as usize]
+    }
+
+    pub fn black_to_move_key(&self) -> u64 {
+        self.black_to_move
+    }
+
+    ///Gets the key for one castling right, side is King for kingside and Queen for queenside.
+    pub fn castling_key(&self, color: PieceColor, side: PieceGroup) -> u64 {
+        let side_index = match side {
+            PieceGroup::King => 0,
+            _ => 1,
+        };
+        self.castling[Piece::color_to_index(color) * 2 + side_index]
+    }
+
+    pub fn en_passant_key(&self, square: u8) -> u64 {
+        self.en_passant_files[(square % 8) as usize]
+    }
+
+    ///Gets the key for holding count pieces at pocket_index of the color's pocket, empty pockets add nothing.
+    pub fn pocket_key(&self, color: PieceColor, pocket_index: usize, count: u8) -> u64 {
+        match count {
+            0 => 0,
+            count => ZobristKeys::mix(
+                self.pockets[Piece::color_to_index(color)][pocket_index] ^ count as u64,
+            ),
+        }
+    }
+
+    ///Gets the key for the piece on square being promoted, crazyhouse pockets it as a pawn.
+    pub fn promoted_key(&self, square: u8) -> u64 {
+        self.promoted_squares[square as usize]
+    }
+
+    ///Gets the key for the color having given count checks, no checks adds nothing.
+    pub fn checks_given_key(&self, color: PieceColor, count: u8) -> u64 {
+        match count {
+            0 => 0,
+            count => {
+                ZobristKeys::mix(self.checks_given[Piece::color_to_index(color)] ^ count as u64)
+            }
+        }
+    }
+}
+
+///Computes a position's hash from scratch, the incrementally kept Board::zobrist_hash must always equal it.
+pub fn compute_hash(board: &Board, en_passant: Option<u8>) -> u64 {
+    let mut hash = 0u64;
+
+    for (square, occupant) in (0..64u8).zip(&board.squares) {
+        if let Some(piece) = occupant {
+            hash ^= ZOBRIST_KEYS.piece_key(*piece, square);
+        }
+    }
+    if board.turn_color == PieceColor::Black {
+        hash ^= ZOBRIST_KEYS.black_to_move_key();
+    }
+    for color in [PieceColor::White, PieceColor::Black] {
+        let color_index = Piece::color_to_index(color);
+        if board.can_kingside_castle[color_index] {
+            hash ^= ZOBRIST_KEYS.castling_key(color, PieceGroup::King);
+        }
+        if board.can_queenside_castle[color_index] {
+            hash ^= ZOBRIST_KEYS.castling_key(color, PieceGroup::Queen);
+        }
+    }
+    if let Some(square) = en_passant {
+        hash ^= ZOBRIST_KEYS.en_passant_key(square);
+    }
+    let mut promoted_pieces = board.promoted_pieces;
+    while promoted_pieces != 0 {
+        hash ^= ZOBRIST_KEYS.promoted_key(promoted_pieces.trailing_zeros() as u8);
+        promoted_pieces &= promoted_pieces - 1;
+    }
+    for color in [PieceColor::White, PieceColor::Black] {
+        for (pocket_index, count) in board.pockets[Piece::color_to_index(color)]
+            .iter()
+            .enumerate()
+        {
+            hash ^= ZOBRIST_KEYS.pocket_key(color, pocket_index, *count);
+        }
+        hash ^=
+            ZOBRIST_KEYS.checks_given_key(color, board.checks_given[Piece::color_to_index(color)]);
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bitboards::Bitboards,
+        fen::{parse_fen, parse_variant_fen},
+        game::Game,
+        move_list::GenerationMode,
+        position::Positions,
+        variant::Variant,
+    };
+
+    fn assert_hash_consistent(board: &Board, bitboards: &Bitboards, depth: u32) {
+        assert_eq!(
+            board.zobrist_hash,
+            compute_hash(board, bitboards.get_en_passant_square())
+        );
+        if depth == 0 {
+            return;
+        }
+
+        let mut bitboards = bitboards.clone();
+        for legal_move in &bitboards.generate_legal_moves(board, GenerationMode::All) {
+            let mut next_board = board.clone();
+            let mut next_bitboards = bitboards.clone();
+            let move_record = next_bitboards
+                .make_move(&mut next_board, *legal_move)
+                .unwrap();
+            next_board.toggle_turn_color();
+            assert_hash_consistent(&next_board, &next_bitboards, depth - 1);
+
+            //unmaking restores the exact hash
+            next_bitboards.unmake_move(&mut next_board, &move_record);
+            next_board.toggle_turn_color();
+            assert_eq!(next_board.zobrist_hash, board.zobrist_hash);
+        }
+    }
+
+    #[test]
+    fn incremental_hash_matches_recomputed_hash() {
+        for (fen, depth) in [
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                3,
+            ),
+            (
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                2,
+            ),
+            (
+                "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+                2,
+            ),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 3),
+        ] {
+            let (board, bitboards) = parse_fen(fen).unwrap();
+            assert_hash_consistent(&board, &bitboards, depth);
+        }
+
+        //pockets are part of the hash in crazyhouse
+        let (board, bitboards) = parse_variant_fen(
+            "4k3/1P4q~1/8/8/8/8/1p6/R3K3[Pn] b Q - 0 1",
+            Variant::Crazyhouse,
+            false,
+        )
+        .unwrap();
+        assert_hash_consistent(&board, &bitboards, 2);
+        let (unpromoted, _) = parse_variant_fen(
+            "4k3/1P4q1/8/8/8/8/1p6/R3K3[Pn] b Q - 0 1",
+            Variant::Crazyhouse,
+            false,
+        )
+        .unwrap();
+        assert_ne!(board.zobrist_hash, unpromoted.zobrist_hash);
+
+        //so are the checks given in three-check
+        let (board, bitboards) = parse_variant_fen(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 2+3 0 1",
+            Variant::ThreeCheck,
+            false,
+        )
+        .unwrap();
+        assert_hash_consistent(&board, &bitboards, 3);
+        let (other_checks, _) = parse_variant_fen(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 3+3 0 1",
+            Variant::ThreeCheck,
+            false,
+        )
+        .unwrap();
+        assert_ne!(board.zobrist_hash, other_checks.zobrist_hash);
+
+        //explosions clear several squares and castling rights at once
+        let (board, bitboards) = parse_variant_fen(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            Variant::Atomic,
+            false,
+        )
+        .unwrap();
+        assert_hash_consistent(&board, &bitboards, 2);
+    }
+
+    #[test]
+    fn transpositions_share_a_hash() {
+        let play = |moves: &[(Positions, Positions)]| {
+            let mut game = Game::new();
+            for (origin, destination) in moves {
+                game.make_move(origin.to_index(), destination.to_index(), None)
+                    .unwrap();
+            }
+            game.board.zobrist_hash
+        };
+
+        assert_eq!(
+            play(&[
+                (Positions::G1, Positions::F3),
+                (Positions::G8, Positions::F6),
+                (Positions::B1, Positions::C3),
+            ]),
+            play(&[
+                (Positions::B1, Positions::C3),
+                (Positions::G8, Positions::F6),
+                (Positions::G1, Positions::F3),
+            ])
+        );
+        assert_eq!(
+            play(&[
+                (Positions::G1, Positions::F3),
+                (Positions::G8, Positions::F6),
+                (Positions::F3, Positions::G1),
+                (Positions::F6, Positions::G8),
+            ]),
+            Board::new().zobrist_hash
+        );
+
+        //same pieces, other side to move
+        let (black_to_move, _) =
+            parse_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        assert_ne!(black_to_move.zobrist_hash, Board::new().zobrist_hash);
+    }
+}
@@ -0,0 +1,408 @@
+use chess_core::{
+    Game, PieceColor, SearchLimits, SearchResult, Searcher, chess960::STANDARD_POSITION_INDEX,
+    notation::parse_uci, search::MAX_PLY,
+};
+use std::{
+    io::{self, BufRead},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+const DEFAULT_HASH_MB: usize = 8;
+const MAX_HASH_MB: usize = 1024;
+const DEFAULT_MOVES_TO_GO: u32 = 30; //assumed moves left when the GUI doesn't say
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50); //kept back for GUI and pipe latency
+
+///Speaks the Universal Chess Interface over stdin and stdout.
+fn main() {
+    let mut engine = Engine::new();
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if !engine.handle_command(&line) {
+            break;
+        }
+    }
+}
+
+struct Engine {
+    game: Game,
+    searcher: Option<Searcher>, //None while a search thread owns it
+    search_thread: Option<JoinHandle<Searcher>>,
+    stop_signal: Arc<AtomicBool>,
+    is_chess960: bool, //castling is then sent and expected as the king taking its rook
+}
+
+impl Engine {
+    fn new() -> Self {
+        let mut searcher = Searcher::new();
+        searcher.set_table_size_mb(DEFAULT_HASH_MB);
+        Self {
+            game: Game::new(),
+            stop_signal: searcher.get_stop_signal(),
+            searcher: Some(searcher),
+            search_thread: None,
+            is_chess960: false,
+        }
+    }
+
+    ///Runs one command line, returning false once told to quit.
+    fn handle_command(&mut self, line: &str) -> bool {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.first().copied() {
+            Some("uci") => {
+                println!("id name chess {}", env!("CARGO_PKG_VERSION"));
+                println!("id author the chess contributors");
+                println!(
+                    "option name Hash type spin default {} min 1 max {}",
+                    DEFAULT_HASH_MB, MAX_HASH_MB
+                );
+                println!("option name Clear Hash type button");
+                println!("option name UCI_Chess960 type check default false");
+                println!("uciok");
+            }
+            Some("isready") => println!("readyok"),
+            Some("ucinewgame") => self.new_game(),
+            Some("setoption") => self.set_option(&tokens[1..]),
+            Some("position") => match parse_position(&tokens[1..], self.is_chess960) {
+                Ok(game) => self.game = game,
+                Err(error) => println!("info string {}", error),
+            },
+            Some("go") => self.go(parse_go(&tokens[1..])),
+            Some("stop") => self.stop(),
+            Some("quit") => {
+                self.stop();
+                return false;
+            }
+            //unknown commands are ignored, as the protocol asks
+            _ => {}
+        }
+        true
+    }
+
+    ///Waits for any running search to finish and hands back the searcher.
+    fn get_searcher(&mut self) -> &mut Searcher {
+        if let Some(search_thread) = self.search_thread.take() {
+            self.searcher = Some(search_thread.join().expect("search thread panicked"));
+        }
+        self.searcher
+            .as_mut()
+            .expect("searcher is back once the thread is joined")
+    }
+
+    fn new_game(&mut self) {
+        self.get_searcher().clear();
+        self.game = Game::new();
+    }
+
+    fn set_option(&mut self, tokens: &[&str]) {
+        let (name, value) = parse_option(tokens);
+        match name.to_ascii_lowercase().as_str() {
+            "hash" => match value.and_then(|value| value.parse::<usize>().ok()) {
+                Some(megabytes) => self
+                    .get_searcher()
+                    .set_table_size_mb(megabytes.clamp(1, MAX_HASH_MB)),
+                None => println!("info string Hash needs a size in MB"),
+            },
+            "clear hash" => self.get_searcher().clear(),
+            "uci_chess960" => match value.as_deref() {
+                Some("true") => self.is_chess960 = true,
+                Some("false") => self.is_chess960 = false,
+                _ => println!("info string UCI_Chess960 needs true or false"),
+            },
+            _ => println!("info string unknown option '{}'", name),
+        }
+    }
+
+    fn go(&mut self, go: GoCommand) {
+        self.get_searcher();
+        let mut searcher = self.searcher.take().expect("no search is running");
+        self.stop_signal.store(false, Ordering::Relaxed);
+
+        let (board, bitboards) = (self.game.board.clone(), self.game.bitboards.clone());
+        let limits = go.get_limits(board.turn_color);
+        let stop_signal = Arc::clone(&self.stop_signal);
+
+        self.search_thread = Some(thread::spawn(move || {
+            let start = Instant::now();
+            let result = searcher.search_with_progress(&board, &bitboards, limits, |iteration| {
+                println!("{}", format_info(iteration, start.elapsed()))
+            });
+
+            //an infinite search may only report its move once told to stop
+            while go.is_infinite && !stop_signal.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(5));
+            }
+            let best_move = result
+                .best_move
+                .map_or("0000".to_string(), |best_move| best_move.to_uci());
+            println!("bestmove {}", best_move);
+            searcher
+        }));
+    }
+
+    fn stop(&mut self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+        self.get_searcher();
+    }
+}
+
+///The limits given to a go command, times in milliseconds.
+#[derive(Debug, Default, PartialEq)]
+struct GoCommand {
+    depth: Option<u32>,
+    nodes: Option<u64>,
+    movetime: Option<u64>,
+    white_time: Option<u64>,
+    black_time: Option<u64>,
+    white_increment: Option<u64>,
+    black_increment: Option<u64>,
+    moves_to_go: Option<u32>,
+    is_infinite: bool,
+}
+
+impl GoCommand {
+    fn get_limits(&self, turn_color: PieceColor) -> SearchLimits {
+        if self.is_infinite {
+            return SearchLimits::default();
+        }
+        let (remaining, increment) = match turn_color {
+            PieceColor::White => (self.white_time, self.white_increment),
+            PieceColor::Black => (self.black_time, self.black_increment),
+        };
+        let clock_time = remaining.map(|remaining| {
+            get_move_time(
+                Duration::from_millis(remaining),
+                Duration::from_millis(increment.unwrap_or(0)),
+                self.moves_to_go,
+            )
+        });
+
+        SearchLimits {
+            depth: self.depth,
+            nodes: self.nodes,
+            time: self.movetime.map(Duration::from_millis).or(clock_time),
+        }
+    }
+}
+
+///Spreads the remaining clock over the moves left, adding most of the increment.
+fn get_move_time(remaining: Duration, increment: Duration, moves_to_go: Option<u32>) -> Duration {
+    let moves_to_go = moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+    let allotted = remaining / moves_to_go + increment * 3 / 4;
+    allotted
+        .min(remaining.saturating_sub(MOVE_OVERHEAD))
+        .max(Duration::from_millis(1))
+}
+
+fn parse_go(tokens: &[&str]) -> GoCommand {
+    let mut go = GoCommand::default();
+    let mut tokens = tokens.iter();
+
+    while let Some(token) = tokens.next() {
+        let mut next_number = || tokens.next().and_then(|value| value.parse::<u64>().ok());
+        match *token {
+            "depth" => go.depth = next_number().map(|depth| depth.min(MAX_PLY as u64) as u32),
+            "nodes" => go.nodes = next_number(),
+            "movetime" => go.movetime = next_number(),
+            "wtime" => go.white_time = next_number(),
+            "btime" => go.black_time = next_number(),
+            "winc" => go.white_increment = next_number(),
+            "binc" => go.black_increment = next_number(),
+            "movestogo" => go.moves_to_go = next_number().map(|moves| moves as u32),
+            "infinite" => go.is_infinite = true,
+            _ => {}
+        }
+    }
+
+    go
+}
+
+///Builds the game for "startpos" or "fen <fen>", then plays the moves after "moves".
+fn parse_position(tokens: &[&str], is_chess960: bool) -> Result<Game, String> {
+    let moves_index = tokens
+        .iter()
+        .position(|token| *token == "moves")
+        .unwrap_or(tokens.len());
+    let mut game = match (tokens.first().copied(), is_chess960) {
+        (Some("startpos"), false) => Game::new(),
+        (Some("startpos"), true) => Game::new_chess960(STANDARD_POSITION_INDEX).unwrap_or_default(),
+        (Some("fen"), false) => {
+            Game::from_fen(&tokens[1..moves_index].join(" ")).map_err(|error| error.to_string())?
+        }
+        (Some("fen"), true) => Game::from_chess960_fen(&tokens[1..moves_index].join(" "))
+            .map_err(|error| error.to_string())?,
+        _ => return Err("position needs startpos or fen".to_string()),
+    };
+
+    for uci in tokens.iter().skip(moves_index + 1) {
+        let uci_move = parse_uci(uci).map_err(|error| error.to_string())?;
+        game.make_notation_move(uci_move)
+            .map_err(|error| format!("{}: {}", uci, error))?;
+    }
+
+    Ok(game)
+}
+
+///Splits "name <name> value <value>", names may contain spaces.
+fn parse_option(tokens: &[&str]) -> (String, Option<String>) {
+    let value_index = tokens
+        .iter()
+        .position(|token| *token == "value")
+        .unwrap_or(tokens.len());
+    let name = tokens[..value_index]
+        .iter()
+        .skip_while(|token| **token == "name")
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let value = match value_index < tokens.len() {
+        true => Some(tokens[value_index + 1..].join(" ")),
+        false => None,
+    };
+    (name, value)
+}
+
+fn format_info(result: &SearchResult, elapsed: Duration) -> String {
+    let score = match result.mate_in() {
+        Some(moves) => format!("mate {}", moves),
+        None => format!("cp {}", result.score),
+    };
+    let milliseconds = elapsed.as_millis().max(1) as u64;
+    let mut info = format!(
+        "info depth {} score {} nodes {} nps {} time {}",
+        result.depth,
+        score,
+        result.nodes,
+        result.nodes * 1000 / milliseconds,
+        milliseconds
+    );
+    for (index, pv_move) in result.principal_variation.iter().enumerate() {
+        if index == 0 {
+            info.push_str(" pv");
+        }
+        info.push(' ');
+        info.push_str(&pv_move.to_uci());
+    }
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess_core::fen::generate_fen;
+
+    #[test]
+    fn parses_go_limits() {
+        let go = parse_go(&[
+            "wtime", "60000", "btime", "30000", "winc", "1000", "depth", "8",
+        ]);
+        assert_eq!(go.white_time, Some(60_000));
+        assert_eq!(go.black_time, Some(30_000));
+        assert_eq!(go.white_increment, Some(1_000));
+        assert_eq!(go.depth, Some(8));
+        assert!(!go.is_infinite);
+
+        let limits = go.get_limits(PieceColor::Black);
+        assert_eq!(limits.depth, Some(8));
+        assert_eq!(limits.time, Some(Duration::from_millis(1_000)));
+
+        assert_eq!(
+            parse_go(&["infinite"]).get_limits(PieceColor::White),
+            SearchLimits::default()
+        );
+        assert_eq!(
+            parse_go(&["movetime", "250", "wtime", "60000"])
+                .get_limits(PieceColor::White)
+                .time,
+            Some(Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn move_time_stays_within_clock() {
+        let low_clock = get_move_time(Duration::from_millis(60), Duration::from_secs(2), None);
+        assert!(low_clock <= Duration::from_millis(10));
+        assert_eq!(
+            get_move_time(Duration::from_secs(10), Duration::ZERO, Some(1)),
+            Duration::from_secs(10) - MOVE_OVERHEAD
+        );
+    }
+
+    #[test]
+    fn parses_positions() {
+        let game = parse_position(&["startpos", "moves", "e2e4", "e7e5", "g1f3"], false).unwrap();
+        assert_eq!(
+            generate_fen(&game.board, &game.bitboards),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+
+        let fen = "4k3/P7/8/8/8/8/8/4K3 w - - 0 1";
+        let command = format!("fen {} moves a7a8q", fen);
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+        let game = parse_position(&tokens, false).unwrap();
+        assert_eq!(
+            generate_fen(&game.board, &game.bitboards),
+            "Q3k3/8/8/8/8/8/8/4K3 b - - 0 1"
+        );
+
+        assert!(parse_position(&["startpos", "moves", "e3e4"], false).is_err());
+        assert!(parse_position(&["startpos", "moves", "e2e5"], false).is_err());
+        assert!(parse_position(&["fen", "not", "a", "fen"], false).is_err());
+
+        //Chess960 GUIs send castling as the king taking its own rook
+        let command = "fen rk5r/8/8/8/8/8/8/R5KR w HAha - 0 1 moves g1h1 b8a8";
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+        let game = parse_position(&tokens, true).unwrap();
+        assert_eq!(
+            generate_fen(&game.board, &game.bitboards),
+            "2kr3r/8/8/8/8/8/8/R4RK1 w - - 2 2"
+        );
+        assert!(parse_position(&tokens, false).is_err());
+    }
+
+    #[test]
+    fn position_command_sets_the_game() {
+        let mut engine = Engine::new();
+        assert!(engine.handle_command("position startpos moves e2e4 e7e5"));
+        assert_eq!(
+            generate_fen(&engine.game.board, &engine.game.bitboards),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+        );
+
+        assert!(engine.handle_command("setoption name UCI_Chess960 value true"));
+        assert!(
+            engine.handle_command("position fen rk5r/8/8/8/8/8/8/R5KR w HAha - 0 1 moves g1h1")
+        );
+        assert_eq!(
+            generate_fen(&engine.game.board, &engine.game.bitboards),
+            "rk5r/8/8/8/8/8/8/R4RK1 b kq - 1 1"
+        );
+
+        //a bad position leaves the last one in place
+        assert!(engine.handle_command("position startpos moves e2e5"));
+        assert_eq!(
+            generate_fen(&engine.game.board, &engine.game.bitboards),
+            "rk5r/8/8/8/8/8/8/R4RK1 b kq - 1 1"
+        );
+        assert!(!engine.handle_command("quit"));
+    }
+
+    #[test]
+    fn parses_options() {
+        assert_eq!(
+            parse_option(&["name", "Hash", "value", "64"]),
+            ("Hash".to_string(), Some("64".to_string()))
+        );
+        assert_eq!(
+            parse_option(&["name", "Clear", "Hash"]),
+            ("Clear Hash".to_string(), None)
+        );
+    }
+}
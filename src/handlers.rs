@@ -1,2 +1,12 @@
 pub mod board;
+pub mod draw;
+pub mod engine;
+pub mod error;
+pub mod evaluate;
+pub mod extract;
+pub mod fen;
+pub mod games;
+pub mod history;
 pub mod moves;
+pub mod perft;
+pub mod socket;
@@ -1,16 +1,27 @@
-use crate::models::response::{AppState, SquaresAndMoves};
-use axum::{Json, debug_handler, extract::State};
-use hyper::StatusCode;
+use crate::{
+    handlers::error::ApiError,
+    models::{
+        registry::GameId,
+        response::{AppState, SquaresAndMoves},
+    },
+};
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+};
 
 #[debug_handler]
 pub async fn get_all_moves_handler(
-    State(AppState { board, bitboards }): State<AppState>,
-) -> Result<Json<SquaresAndMoves>, StatusCode> {
-    let mut locked_board = board.lock().await;
-    let mut locked_bitboards = bitboards.lock().await;
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<SquaresAndMoves>, ApiError> {
+    let game = games
+        .lock()
+        .await
+        .get_game(game_id)
+        .ok_or_else(|| ApiError::game_not_found(game_id))?;
+    let mut locked_game = game.lock().await;
+    let game = &mut *locked_game;
 
-    Ok(Json(SquaresAndMoves {
-        moves: locked_bitboards.get_all_legal_moves(&mut locked_board),
-        squares: locked_board.squares.clone(),
-    }))
+    Ok(Json(SquaresAndMoves::new(game)))
 }
@@ -0,0 +1,57 @@
+use crate::{
+    handlers::error::ApiError,
+    models::{
+        registry::GameId,
+        response::{AppState, GameUpdate, SquaresAndMoves},
+    },
+};
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+};
+use hyper::StatusCode;
+
+#[debug_handler]
+pub async fn claim_draw_handler(
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<SquaresAndMoves>, ApiError> {
+    let game = games
+        .lock()
+        .await
+        .get_game(game_id)
+        .ok_or_else(|| ApiError::game_not_found(game_id))?;
+    let mut locked_game = game.lock().await;
+    let game = &mut *locked_game;
+
+    let legal_moves = game.bitboards.get_all_legal_moves(&mut game.board);
+    if game
+        .bitboards
+        .get_game_status(&game.board, &legal_moves)
+        .is_game_over()
+    {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "game_over",
+            "the game is over, a draw cannot be claimed",
+        ));
+    }
+
+    match game.bitboards.get_claimable_draw(&game.board) {
+        Some(draw_reason) => game.board.claimed_draw = Some(draw_reason),
+        None => {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "no_claimable_draw",
+                "no draw can be claimed in this position",
+            ));
+        }
+    }
+
+    let position = SquaresAndMoves::new(game);
+    games
+        .lock()
+        .await
+        .publish_update(game_id, GameUpdate::new(game, position.clone()));
+    Ok(Json(position))
+}
@@ -0,0 +1,116 @@
+use crate::{
+    handlers::error::ApiError,
+    models::{
+        registry::GameId,
+        response::{AppState, EngineMoveParams, EngineMoveResponse, GameUpdate, SquaresAndMoves},
+    },
+};
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+};
+use chess_core::{MoveError, SearchLimits, Searcher, notation::NotationMove};
+use hyper::StatusCode;
+use std::time::Duration;
+
+const MAX_ENGINE_DEPTH: u32 = 32;
+const MAX_ENGINE_MOVETIME_MS: u64 = 30_000;
+const DEFAULT_ENGINE_MOVETIME_MS: u64 = 1_000;
+
+#[debug_handler]
+pub async fn engine_move_handler(
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+    params: Option<Json<EngineMoveParams>>,
+) -> Result<Json<EngineMoveResponse>, ApiError> {
+    let limits = get_search_limits(params.map(|Json(params)| params).unwrap_or_default())?;
+
+    let game = games
+        .lock()
+        .await
+        .get_game(game_id)
+        .ok_or_else(|| ApiError::game_not_found(game_id))?;
+    //held through the search so no move can be made on the position being searched
+    let mut locked_game = game.lock().await;
+    if locked_game.get_status().is_game_over() {
+        return Err(MoveError::GameOver.into());
+    }
+
+    let (board, bitboards) = (locked_game.board.clone(), locked_game.bitboards.clone());
+    //searches run for up to the move time, keep them off the async workers
+    let result =
+        tokio::task::spawn_blocking(move || Searcher::new().search(&board, &bitboards, limits))
+            .await
+            .map_err(|error| {
+                ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "search_failed",
+                    error.to_string(),
+                )
+            })?;
+    let Some(best_move) = result.best_move else {
+        return Err(MoveError::GameOver.into());
+    };
+
+    let game = &mut *locked_game;
+    let played_move = game.make_notation_move(NotationMove::from(best_move))?;
+
+    let position = SquaresAndMoves::new(game);
+    games
+        .lock()
+        .await
+        .publish_update(game_id, GameUpdate::new(game, position.clone()));
+    Ok(Json(EngineMoveResponse {
+        uci: best_move.to_uci(),
+        san: played_move.san,
+        mate_in: result.mate_in(),
+        score: result.score,
+        depth: result.depth,
+        nodes: result.nodes,
+        principal_variation: result
+            .principal_variation
+            .iter()
+            .map(|pv_move| pv_move.to_uci())
+            .collect(),
+        position,
+    }))
+}
+
+///Checks the requested limits, searching for the default move time when none are given.
+fn get_search_limits(
+    EngineMoveParams {
+        depth,
+        nodes,
+        movetime_ms,
+    }: EngineMoveParams,
+) -> Result<SearchLimits, ApiError> {
+    let invalid_limits =
+        |message: String| ApiError::new(StatusCode::BAD_REQUEST, "invalid_limits", message);
+    if depth.is_some_and(|depth| !(1..=MAX_ENGINE_DEPTH).contains(&depth)) {
+        return Err(invalid_limits(format!(
+            "depth must be between 1 and {}",
+            MAX_ENGINE_DEPTH
+        )));
+    }
+    if nodes == Some(0) {
+        return Err(invalid_limits("nodes must be at least 1".to_string()));
+    }
+    if movetime_ms.is_some_and(|movetime_ms| !(1..=MAX_ENGINE_MOVETIME_MS).contains(&movetime_ms)) {
+        return Err(invalid_limits(format!(
+            "movetime_ms must be between 1 and {}",
+            MAX_ENGINE_MOVETIME_MS
+        )));
+    }
+
+    //an unbounded search would never return, so the move time always applies
+    let movetime_ms = match (depth, nodes, movetime_ms) {
+        (_, _, Some(movetime_ms)) => movetime_ms,
+        (None, None, None) => DEFAULT_ENGINE_MOVETIME_MS,
+        _ => MAX_ENGINE_MOVETIME_MS,
+    };
+    Ok(SearchLimits {
+        depth,
+        nodes,
+        time: Some(Duration::from_millis(movetime_ms)),
+    })
+}
@@ -0,0 +1,76 @@
+use crate::models::{registry::GameId, response::ErrorResponse};
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use chess_core::{MoveError, fen::FenError, notation::NotationError, pgn::PgnError};
+use hyper::StatusCode;
+
+///A failed request, rendered as a JSON body with a stable code clients can match on and a readable message.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn game_not_found(game_id: GameId) -> Self {
+        ApiError::new(
+            StatusCode::NOT_FOUND,
+            "game_not_found",
+            format!("game {} does not exist", game_id),
+        )
+    }
+
+    ///Gets the JSON body without the status, e.g. to send over a socket.
+    pub fn into_body(self) -> ErrorResponse {
+        ErrorResponse {
+            code: self.code.to_string(),
+            message: self.message,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.into_body())).into_response()
+    }
+}
+
+impl From<MoveError> for ApiError {
+    fn from(error: MoveError) -> Self {
+        ApiError::new(StatusCode::BAD_REQUEST, error.code(), error.to_string())
+    }
+}
+
+impl From<NotationError> for ApiError {
+    fn from(error: NotationError) -> Self {
+        let code = match error {
+            NotationError::InvalidSyntax(_) => "invalid_notation",
+            NotationError::IllegalMove(_) => "illegal_move",
+            NotationError::AmbiguousMove(_) => "ambiguous_move",
+        };
+        ApiError::new(StatusCode::BAD_REQUEST, code, error.to_string())
+    }
+}
+
+impl From<FenError> for ApiError {
+    fn from(error: FenError) -> Self {
+        ApiError::new(StatusCode::BAD_REQUEST, "invalid_fen", error.to_string())
+    }
+}
+
+impl From<PgnError> for ApiError {
+    fn from(error: PgnError) -> Self {
+        ApiError::new(StatusCode::BAD_REQUEST, "invalid_pgn", error.to_string())
+    }
+}
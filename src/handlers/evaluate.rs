@@ -0,0 +1,41 @@
+use crate::{
+    handlers::{
+        error::ApiError,
+        extract::{ApiJson, ApiQuery},
+    },
+    models::response::EvaluateParams,
+};
+use axum::{Json, debug_handler};
+use chess_core::{Bitboards, EvalParams, Evaluation, evaluation::evaluate, fen::parse_fen};
+
+#[debug_handler]
+pub async fn evaluate_handler(
+    ApiQuery(EvaluateParams { fen, .. }): ApiQuery<EvaluateParams>,
+) -> Result<Json<Evaluation>, ApiError> {
+    evaluate_position(fen, None)
+}
+
+///Evaluates with custom weights sent in the body, e.g. to try out changes before loading them.
+#[debug_handler]
+pub async fn evaluate_with_params_handler(
+    ApiJson(EvaluateParams { fen, params }): ApiJson<EvaluateParams>,
+) -> Result<Json<Evaluation>, ApiError> {
+    evaluate_position(fen, params)
+}
+
+#[debug_handler]
+pub async fn get_eval_params_handler() -> Json<EvalParams> {
+    Json(EvalParams::default())
+}
+
+fn evaluate_position(
+    fen: Option<String>,
+    params: Option<EvalParams>,
+) -> Result<Json<Evaluation>, ApiError> {
+    let bitboards = match fen {
+        Some(fen) => parse_fen(&fen)?.1,
+        None => Bitboards::new(),
+    };
+
+    Ok(Json(evaluate(&bitboards, &params.unwrap_or_default())))
+}
@@ -0,0 +1,90 @@
+use crate::handlers::error::ApiError;
+use axum::extract::{
+    FromRequest, FromRequestParts, OptionalFromRequest, Request,
+    rejection::{JsonRejection, QueryRejection},
+};
+use serde::de::DeserializeOwned;
+
+///A JSON body extractor whose rejections are rendered as an ApiError instead of plain text.
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(ApiError))]
+pub struct ApiJson<T>(pub T);
+
+//lets handlers take an optional body, which is None when no content type is sent
+impl<T, S> OptionalFromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        let json = <axum::Json<T> as OptionalFromRequest<S>>::from_request(request, state).await?;
+        Ok(json.map(|axum::Json(value)| ApiJson(value)))
+    }
+}
+
+///A query string extractor whose rejections are rendered as an ApiError instead of plain text.
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(ApiError))]
+pub struct ApiQuery<T>(pub T);
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        let code = match rejection {
+            JsonRejection::JsonDataError(_) => "invalid_json_data",
+            JsonRejection::JsonSyntaxError(_) => "invalid_json_syntax",
+            JsonRejection::MissingJsonContentType(_) => "missing_json_content_type",
+            _ => "unreadable_body",
+        };
+        ApiError::new(rejection.status(), code, rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::new(rejection.status(), "invalid_query", rejection.body_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::handlers::evaluate::evaluate_with_params_handler;
+    use axum::{
+        body::{Body, to_bytes},
+        handler::Handler,
+        http::Request,
+    };
+    use hyper::{StatusCode, header::CONTENT_TYPE};
+    use serde_json::Value;
+
+    async fn post_evaluate(content_type: &str, body: &'static str) -> (StatusCode, Value) {
+        let request = Request::post("/evaluate")
+            .header(CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .unwrap();
+        let response = evaluate_with_params_handler.call(request, ()).await;
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn rejected_bodies_are_json_errors() {
+        let (status, body) = post_evaluate("application/json", "{\"fen\":").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "invalid_json_syntax");
+        assert!(body["message"].is_string());
+
+        let (status, body) = post_evaluate("application/json", "{\"fen\":1}").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["code"], "invalid_json_data");
+
+        let (status, body) = post_evaluate("text/plain", "{}").await;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(body["code"], "missing_json_content_type");
+
+        let (status, _) = post_evaluate("application/json", "{}").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
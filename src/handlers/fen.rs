@@ -0,0 +1,57 @@
+use crate::{
+    handlers::{error::ApiError, extract::ApiJson},
+    models::{
+        registry::GameId,
+        response::{AppState, FenParams, GameUpdate, SquaresAndMoves},
+    },
+};
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+};
+use chess_core::{Game, fen::generate_fen};
+
+#[debug_handler]
+pub async fn get_fen_handler(
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<FenParams>, ApiError> {
+    let game = games
+        .lock()
+        .await
+        .get_game(game_id)
+        .ok_or_else(|| ApiError::game_not_found(game_id))?;
+    let locked_game = game.lock().await;
+
+    Ok(Json(FenParams {
+        fen: generate_fen(&locked_game.board, &locked_game.bitboards),
+    }))
+}
+
+#[debug_handler]
+pub async fn load_fen_handler(
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+    ApiJson(FenParams { fen }): ApiJson<FenParams>,
+) -> Result<Json<SquaresAndMoves>, ApiError> {
+    let game = games
+        .lock()
+        .await
+        .get_game(game_id)
+        .ok_or_else(|| ApiError::game_not_found(game_id))?;
+    let mut locked_game = game.lock().await;
+    //a loaded position keeps the game's variant and castling rules
+    *locked_game = Game::from_variant_fen(
+        &fen,
+        locked_game.board.variant,
+        locked_game.board.is_chess960,
+    )?;
+    let game = &mut *locked_game;
+
+    let position = SquaresAndMoves::new(game);
+    games
+        .lock()
+        .await
+        .publish_update(game_id, GameUpdate::new(game, position.clone()));
+    Ok(Json(position))
+}
@@ -0,0 +1,142 @@
+use crate::{
+    handlers::{error::ApiError, extract::ApiJson},
+    models::{
+        registry::GameId,
+        response::{
+            AppState, Chess960Params, CreateGameParams, GameResponse, ImportPgnParams,
+            ImportedGame, ImportedGames, SquaresAndMoves,
+        },
+    },
+};
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+};
+use chess_core::{
+    Game, Variant,
+    chess960::{CHESS960_POSITION_COUNT, get_chess960_index_from_seed},
+    fen::generate_fen,
+    pgn::{generate_pgn, parse_pgn, replay_pgn_game},
+};
+use hyper::{
+    StatusCode,
+    header::{CONTENT_TYPE, HeaderName},
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[debug_handler]
+pub async fn create_game_handler(
+    State(AppState { games }): State<AppState>,
+    params: Option<ApiJson<CreateGameParams>>,
+) -> Result<(StatusCode, Json<GameResponse>), ApiError> {
+    let CreateGameParams {
+        fen,
+        tags,
+        chess960,
+        variant,
+    } = params.map(|ApiJson(params)| params).unwrap_or_default();
+    let variant = variant.unwrap_or_default();
+    if chess960.is_some() && variant != Variant::Standard {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "unsupported_variant",
+            format!("Chess960 can't be combined with {}", variant.get_name()),
+        ));
+    }
+    let mut game = match (fen, chess960) {
+        (Some(fen), chess960) => Game::from_variant_fen(&fen, variant, chess960.is_some())?,
+        (None, None) => Game::new_variant(variant),
+        (None, Some(params)) => create_chess960_game(params)?,
+    };
+    game.tags = tags.unwrap_or_default();
+    let position = SquaresAndMoves::new(&mut game);
+    let id = games.lock().await.add_game(game);
+
+    Ok((StatusCode::CREATED, Json(GameResponse { id, position })))
+}
+
+fn create_chess960_game(Chess960Params { index, seed }: Chess960Params) -> Result<Game, ApiError> {
+    let index = match (index, seed) {
+        (Some(index), _) => index,
+        (None, Some(seed)) => get_chess960_index_from_seed(seed),
+        (None, None) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            get_chess960_index_from_seed(now.as_nanos() as u64)
+        }
+    };
+    Game::new_chess960(index).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_chess960_index",
+            format!(
+                "Chess960 index {} is out of range, must be below {}",
+                index, CHESS960_POSITION_COUNT
+            ),
+        )
+    })
+}
+
+#[debug_handler]
+pub async fn delete_game_handler(
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+) -> Result<StatusCode, ApiError> {
+    match games.lock().await.remove_game(game_id) {
+        true => Ok(StatusCode::NO_CONTENT),
+        false => Err(ApiError::game_not_found(game_id)),
+    }
+}
+
+#[debug_handler]
+pub async fn get_pgn_handler(
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+) -> Result<([(HeaderName, &'static str); 1], String), ApiError> {
+    let game = games
+        .lock()
+        .await
+        .get_game(game_id)
+        .ok_or_else(|| ApiError::game_not_found(game_id))?;
+    let locked_game = game.lock().await;
+
+    Ok((
+        [(CONTENT_TYPE, "application/x-chess-pgn")],
+        generate_pgn(&locked_game),
+    ))
+}
+
+#[debug_handler]
+pub async fn import_pgn_handler(
+    State(AppState { games }): State<AppState>,
+    ApiJson(ImportPgnParams { pgn, create_games }): ApiJson<ImportPgnParams>,
+) -> Result<Json<ImportedGames>, ApiError> {
+    let pgn_games = parse_pgn(&pgn)?;
+
+    //replay every game before storing any so a bad file imports nothing
+    let mut replayed_games = vec![];
+    for (index, pgn_game) in pgn_games.iter().enumerate() {
+        let game = replay_pgn_game(pgn_game, index + 1)?;
+        replayed_games.push((pgn_game, game));
+    }
+
+    let mut locked_games = games.lock().await;
+    let imported_games = replayed_games
+        .into_iter()
+        .map(|(pgn_game, game)| ImportedGame {
+            tags: pgn_game.tags.clone(),
+            result: pgn_game.result.clone(),
+            fen: generate_fen(&game.board, &game.bitboards),
+            san_history: game.get_san_history(),
+            id: match create_games.unwrap_or(false) {
+                true => Some(locked_games.add_game(game)),
+                false => None,
+            },
+        })
+        .collect();
+
+    Ok(Json(ImportedGames {
+        games: imported_games,
+    }))
+}
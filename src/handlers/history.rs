@@ -0,0 +1,70 @@
+use crate::{
+    handlers::error::ApiError,
+    models::{
+        registry::GameId,
+        response::{AppState, GameUpdate, SquaresAndMoves},
+    },
+};
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+};
+use hyper::StatusCode;
+
+#[debug_handler]
+pub async fn undo_move_handler(
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<SquaresAndMoves>, ApiError> {
+    let game = games
+        .lock()
+        .await
+        .get_game(game_id)
+        .ok_or_else(|| ApiError::game_not_found(game_id))?;
+    let mut locked_game = game.lock().await;
+    let game = &mut *locked_game;
+
+    if game.undo_move().is_none() {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "nothing_to_undo",
+            "there are no moves to undo",
+        ));
+    }
+
+    let position = SquaresAndMoves::new(game);
+    games
+        .lock()
+        .await
+        .publish_update(game_id, GameUpdate::new(game, position.clone()));
+    Ok(Json(position))
+}
+
+#[debug_handler]
+pub async fn redo_move_handler(
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+) -> Result<Json<SquaresAndMoves>, ApiError> {
+    let game = games
+        .lock()
+        .await
+        .get_game(game_id)
+        .ok_or_else(|| ApiError::game_not_found(game_id))?;
+    let mut locked_game = game.lock().await;
+    let game = &mut *locked_game;
+
+    if game.redo_move().is_none() {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "nothing_to_redo",
+            "there are no undone moves to redo",
+        ));
+    }
+
+    let position = SquaresAndMoves::new(game);
+    games
+        .lock()
+        .await
+        .publish_update(game_id, GameUpdate::new(game, position.clone()));
+    Ok(Json(position))
+}
@@ -1,48 +1,96 @@
-use crate::models::response::{AppState, MoveParams, SquaresAndMoves};
-use axum::{Json, debug_handler, extract::State};
-use hyper::StatusCode;
+use crate::{
+    handlers::{
+        error::ApiError,
+        extract::{ApiJson, ApiQuery},
+    },
+    models::{
+        registry::GameId,
+        response::{
+            AppState, DropParams, GameUpdate, MoveParams, MoveRequest, SanParams, SquaresAndMoves,
+            UciMoves, UciMovesParams, UciParams,
+        },
+    },
+};
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+};
+use chess_core::{
+    Game, GenerationMode, PlayedMove,
+    notation::{NotationMove, parse_san, parse_uci},
+};
 
 #[debug_handler]
 pub async fn move_piece_handler(
-    State(AppState { board, bitboards }): State<AppState>,
-    Json(MoveParams {
-        promotion,
-        origin,
-        destination,
-    }): Json<MoveParams>,
-) -> Result<Json<SquaresAndMoves>, StatusCode> {
-    let mut locked_board = board.lock().await;
-    let mut locked_bitboards = bitboards.lock().await;
-    let (origin_idx, destination_idx) = (origin.to_index(), destination.to_index());
-
-    if let Some(promotion) = promotion {
-        match locked_bitboards.promote_pawn(
-            &mut locked_board,
-            origin_idx,
-            destination_idx,
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+    ApiJson(move_request): ApiJson<MoveRequest>,
+) -> Result<Json<SquaresAndMoves>, ApiError> {
+    let game = games
+        .lock()
+        .await
+        .get_game(game_id)
+        .ok_or_else(|| ApiError::game_not_found(game_id))?;
+    let mut locked_game = game.lock().await;
+    let game = &mut *locked_game;
+
+    play_move_request(game, move_request)?;
+
+    let position = SquaresAndMoves::new(game);
+    games
+        .lock()
+        .await
+        .publish_update(game_id, GameUpdate::new(game, position.clone()));
+    Ok(Json(position))
+}
+
+///Plays a move given by squares, a drop, SAN or UCI, shared by the move route and game sockets.
+pub fn play_move_request(
+    game: &mut Game,
+    move_request: MoveRequest,
+) -> Result<PlayedMove, ApiError> {
+    let notation_move = match move_request {
+        MoveRequest::Squares(MoveParams {
+            origin,
+            destination,
+            promotion,
+        }) => NotationMove {
+            origin: origin.to_index(),
+            destination: destination.to_index(),
             promotion,
-        ) {
-            Ok(_) => {
-                locked_board.toggle_turn_color();
-
-                return Ok(Json(SquaresAndMoves {
-                    moves: locked_bitboards.get_all_legal_moves(&mut locked_board),
-                    squares: locked_board.squares.clone(),
-                }));
-            }
-            Err(status_code) => return Err(status_code),
+            drop: None,
+        },
+        MoveRequest::Drop(DropParams { drop, destination }) => {
+            NotationMove::new_drop(drop, destination.to_index())
         }
-    }
+        MoveRequest::San(SanParams { san }) => parse_san(&game.board, &game.bitboards, &san)?,
+        MoveRequest::Uci(UciParams { uci }) => parse_uci(&uci)?,
+    };
 
-    match locked_bitboards.move_piece(&mut locked_board, origin_idx, destination_idx) {
-        Ok(_) => {
-            locked_board.toggle_turn_color();
+    Ok(game.make_notation_move(notation_move)?)
+}
 
-            return Ok(Json(SquaresAndMoves {
-                moves: locked_bitboards.get_all_legal_moves(&mut locked_board),
-                squares: locked_board.squares.clone(),
-            }));
-        }
-        Err(status_code) => Err(status_code),
-    }
+#[debug_handler]
+pub async fn get_uci_moves_handler(
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+    ApiQuery(UciMovesParams { mode, pseudo_legal }): ApiQuery<UciMovesParams>,
+) -> Result<Json<UciMoves>, ApiError> {
+    let game = games
+        .lock()
+        .await
+        .get_game(game_id)
+        .ok_or_else(|| ApiError::game_not_found(game_id))?;
+    let locked_game = game.lock().await;
+
+    let mode = mode.unwrap_or(GenerationMode::All);
+    let mut bitboards = locked_game.bitboards.clone();
+    let move_list = match pseudo_legal.unwrap_or(false) {
+        true => bitboards.generate_pseudo_legal_moves(&locked_game.board, mode),
+        false => bitboards.generate_legal_moves(&locked_game.board, mode),
+    };
+
+    Ok(Json(UciMoves {
+        moves: move_list.iter().map(|uci_move| uci_move.to_uci()).collect(),
+    }))
 }
@@ -0,0 +1,46 @@
+use crate::{
+    handlers::{error::ApiError, extract::ApiQuery},
+    models::response::{PerftDivision, PerftParams, PerftResult},
+};
+use axum::{Json, debug_handler};
+use chess_core::{Bitboards, Board, fen::parse_fen, perft::divide};
+use hyper::StatusCode;
+
+const MAX_PERFT_DEPTH: u32 = 5;
+
+#[debug_handler]
+pub async fn perft_handler(
+    ApiQuery(PerftParams { depth, fen }): ApiQuery<PerftParams>,
+) -> Result<Json<PerftResult>, ApiError> {
+    if !(1..=MAX_PERFT_DEPTH).contains(&depth) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_depth",
+            format!("depth must be between 1 and {}", MAX_PERFT_DEPTH),
+        ));
+    }
+    let (board, bitboards) = match fen {
+        Some(fen) => parse_fen(&fen)?,
+        None => (Board::new(), Bitboards::new()),
+    };
+
+    //deep counts take seconds, keep them off the async workers
+    let divisions = tokio::task::spawn_blocking(move || divide(&board, &bitboards, depth))
+        .await
+        .map_err(|error| {
+            ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "perft_failed",
+                error.to_string(),
+            )
+        })?;
+
+    Ok(Json(PerftResult {
+        depth,
+        nodes: divisions.iter().map(|(_, nodes)| nodes).sum(),
+        divide: divisions
+            .into_iter()
+            .map(|(uci, nodes)| PerftDivision { uci, nodes })
+            .collect(),
+    }))
+}
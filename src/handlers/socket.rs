@@ -0,0 +1,131 @@
+use crate::{
+    handlers::{error::ApiError, moves::play_move_request},
+    models::{
+        registry::{GameId, GameRegistry},
+        response::{AppState, GameUpdate, MoveRequest, SocketMessage, SquaresAndMoves},
+    },
+};
+use axum::{
+    debug_handler,
+    extract::{
+        Path, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::Response,
+};
+use chess_core::Game;
+use hyper::StatusCode;
+use std::sync::Arc;
+use tokio::sync::{
+    Mutex,
+    broadcast::{
+        Receiver,
+        error::{RecvError, TryRecvError},
+    },
+};
+
+///Upgrades to a socket that is sent the game after every move and accepts moves in the move route's JSON.
+#[debug_handler]
+pub async fn game_socket_handler(
+    State(AppState { games }): State<AppState>,
+    Path(game_id): Path<GameId>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let (game, updates) = {
+        let registry = games.lock().await;
+        match (registry.get_game(game_id), registry.subscribe(game_id)) {
+            (Some(game), Some(updates)) => (game, updates),
+            _ => return Err(ApiError::game_not_found(game_id)),
+        }
+    };
+
+    Ok(upgrade.on_upgrade(move |socket| watch_game(socket, games, game_id, game, updates)))
+}
+
+async fn watch_game(
+    mut socket: WebSocket,
+    games: Arc<Mutex<GameRegistry>>,
+    game_id: GameId,
+    game: Arc<Mutex<Game>>,
+    mut updates: Receiver<GameUpdate>,
+) {
+    let current_update = {
+        let mut locked_game = game.lock().await;
+        //updates are published under the game lock, so anything queued is no newer than this
+        while let Ok(_) | Err(TryRecvError::Lagged(_)) = updates.try_recv() {}
+        let game = &mut *locked_game;
+        let position = SquaresAndMoves::new(game);
+        GameUpdate::new(game, position)
+    };
+    if send_message(&mut socket, SocketMessage::Update(current_update))
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    //the mover hears about a played move through its own update like everyone else
+                    if let Err(error) = play_socket_move(&games, game_id, &game, text.as_str()).await
+                        && send_message(&mut socket, SocketMessage::Error(error.into_body()))
+                            .await
+                            .is_err()
+                    {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                //pings are answered by axum, binary frames are ignored
+                Some(Ok(_)) => {}
+            },
+            update = updates.recv() => match update {
+                Ok(update) => {
+                    if send_message(&mut socket, SocketMessage::Update(update)).await.is_err() {
+                        break;
+                    }
+                }
+                //every update carries the whole position, so the next one catches the socket up
+                Err(RecvError::Lagged(_)) => {}
+                //the game was deleted
+                Err(RecvError::Closed) => {
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                }
+            },
+        }
+    }
+}
+
+async fn play_socket_move(
+    games: &Mutex<GameRegistry>,
+    game_id: GameId,
+    game: &Mutex<Game>,
+    text: &str,
+) -> Result<(), ApiError> {
+    let move_request: MoveRequest = serde_json::from_str(text).map_err(|error| {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_message",
+            error.to_string(),
+        )
+    })?;
+
+    let mut locked_game = game.lock().await;
+    let game = &mut *locked_game;
+    play_move_request(game, move_request)?;
+
+    let position = SquaresAndMoves::new(game);
+    games
+        .lock()
+        .await
+        .publish_update(game_id, GameUpdate::new(game, position));
+    Ok(())
+}
+
+async fn send_message(socket: &mut WebSocket, message: SocketMessage) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(&message).expect("socket messages are always serializable");
+    socket.send(Message::Text(json.into())).await
+}
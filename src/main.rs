@@ -4,8 +4,19 @@ mod models;
 use std::sync::Arc;
 
 use crate::{
-    handlers::{board::get_all_moves_handler, moves::move_piece_handler},
-    models::{bitboards::Bitboards, board::Board, response::AppState},
+    handlers::{
+        board::get_all_moves_handler,
+        draw::claim_draw_handler,
+        engine::engine_move_handler,
+        evaluate::{evaluate_handler, evaluate_with_params_handler, get_eval_params_handler},
+        fen::{get_fen_handler, load_fen_handler},
+        games::{create_game_handler, delete_game_handler, get_pgn_handler, import_pgn_handler},
+        history::{redo_move_handler, undo_move_handler},
+        moves::{get_uci_moves_handler, move_piece_handler},
+        perft::perft_handler,
+        socket::game_socket_handler,
+    },
+    models::{registry::GameRegistry, response::AppState},
 };
 use axum::{
     Router,
@@ -25,21 +36,42 @@ async fn main() {
 
 fn create_router() -> Router {
     Router::new()
-        .route("/board", get(get_all_moves_handler))
-        .route("/move", post(move_piece_handler))
+        .route("/games", post(create_game_handler))
+        .route("/games/import", post(import_pgn_handler))
+        .route(
+            "/games/{id}",
+            get(get_all_moves_handler).delete(delete_game_handler),
+        )
+        .route("/games/{id}/move", post(move_piece_handler))
+        .route("/games/{id}/moves", get(get_uci_moves_handler))
+        .route("/games/{id}/undo", post(undo_move_handler))
+        .route("/games/{id}/redo", post(redo_move_handler))
+        .route("/games/{id}/draw", post(claim_draw_handler))
+        .route("/games/{id}/engine-move", post(engine_move_handler))
+        .route("/games/{id}/ws", get(game_socket_handler))
+        .route("/games/{id}/pgn", get(get_pgn_handler))
+        .route(
+            "/games/{id}/fen",
+            get(get_fen_handler).post(load_fen_handler),
+        )
+        .route("/perft", get(perft_handler))
+        .route(
+            "/evaluate",
+            get(evaluate_handler).post(evaluate_with_params_handler),
+        )
+        .route("/evaluate/params", get(get_eval_params_handler))
         .with_state(create_state())
         .layer(create_cors())
 }
 
 fn create_state() -> AppState {
-    let board = Arc::new(Mutex::new(Board::new()));
-    let bitboards = Arc::new(Mutex::new(Bitboards::new()));
-    AppState { board, bitboards }
+    let games = Arc::new(Mutex::new(GameRegistry::new()));
+    AppState { games }
 }
 
 fn create_cors() -> CorsLayer {
     CorsLayer::new()
         .allow_origin(HeaderValue::from_static("http://localhost:3000"))
-        .allow_methods([Method::GET, Method::POST])
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
         .allow_headers([CONTENT_TYPE])
 }
@@ -1,5 +1,2 @@
-pub mod bitboards;
-pub mod board;
-pub mod piece;
-pub mod position;
+pub mod registry;
 pub mod response;
@@ -0,0 +1,59 @@
+use crate::models::response::GameUpdate;
+use chess_core::Game;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{Mutex, broadcast};
+
+pub type GameId = u32;
+
+const UPDATE_CHANNEL_CAPACITY: usize = 16; //a socket further behind skips to the newest update
+
+pub struct GameRegistry {
+    games: HashMap<GameId, Arc<Mutex<Game>>>,
+    updates: HashMap<GameId, broadcast::Sender<GameUpdate>>, //dropped with the game, closing its sockets
+    next_game_id: GameId,
+}
+
+impl GameRegistry {
+    pub fn new() -> Self {
+        Self {
+            games: HashMap::new(),
+            updates: HashMap::new(),
+            next_game_id: 1,
+        }
+    }
+
+    ///Stores the game under a new id and returns the id.
+    pub fn add_game(&mut self, game: Game) -> GameId {
+        let game_id = self.next_game_id;
+        self.next_game_id += 1;
+        self.games.insert(game_id, Arc::new(Mutex::new(game)));
+        self.updates
+            .insert(game_id, broadcast::channel(UPDATE_CHANNEL_CAPACITY).0);
+        game_id
+    }
+
+    pub fn get_game(&self, game_id: GameId) -> Option<Arc<Mutex<Game>>> {
+        self.games.get(&game_id).cloned()
+    }
+
+    ///Removes the game, returning whether it existed.
+    pub fn remove_game(&mut self, game_id: GameId) -> bool {
+        self.updates.remove(&game_id);
+        self.games.remove(&game_id).is_some()
+    }
+
+    ///Subscribes to the updates published for the game.
+    pub fn subscribe(&self, game_id: GameId) -> Option<broadcast::Receiver<GameUpdate>> {
+        self.updates
+            .get(&game_id)
+            .map(|updates| updates.subscribe())
+    }
+
+    ///Sends the update to every socket watching the game, if any.
+    pub fn publish_update(&self, game_id: GameId, update: GameUpdate) {
+        if let Some(updates) = self.updates.get(&game_id) {
+            //only fails when nobody is subscribed
+            let _ = updates.send(update);
+        }
+    }
+}
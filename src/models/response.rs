@@ -1,8 +1,7 @@
-use crate::models::{
-    bitboards::Bitboards,
-    board::Board,
-    piece::{Piece, PieceGroup},
-    position::Positions,
+use crate::models::registry::{GameId, GameRegistry};
+use chess_core::{
+    DrawReason, EvalParams, Game, GameStatus, GenerationMode, Piece, PieceGroup, PlayedMove,
+    Positions, Variant,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -12,12 +11,123 @@ use tokio::sync::Mutex;
 pub struct SquaresAndMoves {
     pub squares: Vec<Option<Piece>>,
     pub moves: Vec<u64>,
+    pub promotion_moves: Vec<u64>, //subset of moves that need a promotion piece
+    pub status: GameStatus,
+    pub claimable_draw: Option<DrawReason>,
+    pub san_history: Vec<String>,
+    pub zobrist_hash: String, //hex, JSON numbers can't hold all 64 bits
+    pub chess960: bool,       //castling moves then land on the rook's square
+    pub variant: Variant,
+    pub checks_given: Option<[u8; 2]>, //white's then black's, three-check only
+    pub pockets: Option<[[u8; 5]; 2]>, //white's then black's pawns, knights, bishops, rooks and queens in hand, crazyhouse only
+    pub drop_moves: Option<[u64; 5]>,  //squares the side to move can drop each of those pieces on
+}
+
+impl SquaresAndMoves {
+    ///Gets the squares, legal moves, game status, any claimable draw and move history of the game.
+    pub fn new(game: &mut Game) -> Self {
+        let Game {
+            board, bitboards, ..
+        } = game;
+        let moves = bitboards.get_all_legal_moves(board);
+        let status = bitboards.get_game_status(board, &moves);
+        let promotion_moves = bitboards.get_promotion_moves(&moves);
+        let drop_moves = board
+            .variant
+            .has_drops()
+            .then(|| bitboards.get_all_legal_drops(board));
+        let claimable_draw = match status.is_game_over() {
+            true => None,
+            false => bitboards.get_claimable_draw(board),
+        };
+        Self {
+            squares: board.squares.clone(),
+            zobrist_hash: format!("{:016x}", board.zobrist_hash),
+            moves,
+            promotion_moves,
+            status,
+            claimable_draw,
+            san_history: game.get_san_history(),
+            chess960: game.board.is_chess960,
+            variant: game.board.variant,
+            checks_given: game
+                .board
+                .variant
+                .counts_checks()
+                .then_some(game.board.checks_given),
+            pockets: game.board.variant.has_drops().then_some(game.board.pockets),
+            drop_moves,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameResponse {
+    pub id: GameId,
+    #[serde(flatten)]
+    pub position: SquaresAndMoves,
+}
+
+///What a game's sockets are sent whenever its position changes.
+#[derive(Clone, Debug, Serialize)]
+pub struct GameUpdate {
+    pub last_move: Option<LastMove>, //None at the starting position or after undoing every move
+    #[serde(flatten)]
+    pub position: SquaresAndMoves,
+}
+
+impl GameUpdate {
+    pub fn new(game: &Game, position: SquaresAndMoves) -> Self {
+        Self {
+            last_move: game.move_history.last().map(LastMove::from),
+            position,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LastMove {
+    pub origin: Positions,
+    pub destination: Positions,
+    pub promotion: Option<PieceGroup>,
+    pub drop: Option<PieceGroup>, //origin is the destination for drops
+    pub san: String,
+}
+
+impl From<&PlayedMove> for LastMove {
+    fn from(played_move: &PlayedMove) -> Self {
+        let record = &played_move.record;
+        Self {
+            origin: Positions::from_index(record.origin).expect("move origins are on the board"),
+            destination: Positions::from_index(record.destination)
+                .expect("move destinations are on the board"),
+            promotion: record.promotion,
+            drop: record.drop,
+            san: played_move.san.clone(),
+        }
+    }
+}
+
+///A message sent over a game's socket, tagged with its "type".
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SocketMessage {
+    Update(GameUpdate),
+    Error(ErrorResponse), //only sent to the socket whose move failed
 }
 
 #[derive(Clone)]
 pub struct AppState {
-    pub board: Arc<Mutex<Board>>,
-    pub bitboards: Arc<Mutex<Bitboards>>,
+    pub games: Arc<Mutex<GameRegistry>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum MoveRequest {
+    Squares(MoveParams),
+    Drop(DropParams),
+    San(SanParams),
+    Uci(UciParams),
 }
 
 #[derive(Deserialize, Debug)]
@@ -26,3 +136,121 @@ pub struct MoveParams {
     pub destination: Positions,
     pub promotion: Option<PieceGroup>,
 }
+
+#[derive(Deserialize, Debug)]
+pub struct DropParams {
+    pub drop: PieceGroup, //the pocket piece to put on destination, crazyhouse only
+    pub destination: Positions,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SanParams {
+    pub san: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UciParams {
+    pub uci: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UciMovesParams {
+    pub mode: Option<GenerationMode>, //defaults to all moves
+    pub pseudo_legal: Option<bool>,   //include moves that leave the own king in check
+}
+
+#[derive(Serialize, Debug)]
+pub struct UciMoves {
+    pub moves: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FenParams {
+    pub fen: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportPgnParams {
+    pub pgn: String,
+    pub create_games: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ImportedGame {
+    pub id: Option<GameId>,
+    pub tags: Vec<(String, String)>,
+    pub result: Option<String>,
+    pub fen: String,
+    pub san_history: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ImportedGames {
+    pub games: Vec<ImportedGame>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct CreateGameParams {
+    pub fen: Option<String>,
+    pub tags: Option<Vec<(String, String)>>,
+    pub chess960: Option<Chess960Params>, //standard chess if omitted
+    pub variant: Option<Variant>,         //standard rules if omitted
+}
+
+///Picks the Chess960 start position by Scharnagl index or by seed, a random one if both are omitted.
+#[derive(Deserialize, Debug, Default)]
+pub struct Chess960Params {
+    pub index: Option<u16>,
+    pub seed: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct PerftParams {
+    pub depth: u32,
+    pub fen: Option<String>, //starting position if omitted
+}
+
+#[derive(Serialize)]
+pub struct PerftDivision {
+    pub uci: String,
+    pub nodes: u64,
+}
+
+#[derive(Serialize)]
+pub struct PerftResult {
+    pub depth: u32,
+    pub nodes: u64,
+    pub divide: Vec<PerftDivision>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct EngineMoveParams {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub movetime_ms: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EngineMoveResponse {
+    pub uci: String,
+    pub san: String,
+    pub score: i32, //centipawns from the engine's side
+    pub mate_in: Option<i32>,
+    pub depth: u32,
+    pub nodes: u64,
+    pub principal_variation: Vec<String>, //UCI, starting with the played move
+    #[serde(flatten)]
+    pub position: SquaresAndMoves,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct EvaluateParams {
+    pub fen: Option<String>,        //starting position if omitted
+    pub params: Option<EvalParams>, //default weights if omitted
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct ErrorResponse {
+    pub code: String,
+    pub message: String,
+}